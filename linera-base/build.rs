@@ -6,9 +6,11 @@ fn main() {
         web: { all(target_arch = "wasm32", feature = "web") },
         chain: { all(target_arch = "wasm32", not(web)) },
         with_metrics: { all(not(target_arch = "wasm32"), feature = "metrics") },
+        with_rayon: { feature = "rayon" },
         with_reqwest: { feature = "reqwest" },
         with_testing: { any(test, feature = "test") },
         with_revm: { any(test, feature = "revm") },
+        with_unsafe_secret_serialization: { feature = "with-unsafe-secret-serialization" },
 
         // the old version of `getrandom` we pin here is available on all targets, but
         // using it will panic if no suitable source of entropy is found
@@ -23,6 +23,9 @@ use crate::doc_scalar;
 const ED25519_SCHEME_LABEL: &str = "Ed25519";
 
 /// An Ed25519 secret key.
+///
+/// Keys are compared in constant time.
+#[derive(Eq, PartialEq)]
 pub struct Ed25519SecretKey(pub(crate) dalek::SigningKey);
 
 /// An Ed25519 signature public key.
@@ -125,6 +128,12 @@ impl<'de> Deserialize<'de> for Ed25519PublicKey {
     }
 }
 
+impl fmt::Debug for Ed25519SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted for Ed25519 secret key>")
+    }
+}
+
 impl Serialize for Ed25519SecretKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
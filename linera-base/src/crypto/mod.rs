@@ -17,11 +17,14 @@ pub use hash::*;
 use linera_witty::{WitLoad, WitStore, WitType};
 pub use secp256k1::{
     evm::{EvmPublicKey, EvmSecretKey, EvmSignature},
-    Secp256k1PublicKey, Secp256k1SecretKey, Secp256k1Signature,
+    schnorr::Secp256k1SchnorrSignature,
+    Secp256k1PublicKey, Secp256k1RecoverableSignature, Secp256k1SecretKey, Secp256k1Signature,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::data_types::{TimeDelta, Timestamp};
+
 /// The public key of a validator.
 pub type ValidatorPublicKey = secp256k1::Secp256k1PublicKey;
 /// The private key of a validator.
@@ -70,7 +73,9 @@ pub enum AccountPublicKey {
 }
 
 /// The private key of a chain owner.
-#[derive(Serialize, Deserialize)]
+///
+/// Keys are compared in constant time, and the `Debug` output of every variant is redacted.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub enum AccountSecretKey {
     /// Ed25519 secret key.
     Ed25519(ed25519::Ed25519SecretKey),
@@ -170,6 +175,15 @@ impl AccountPublicKey {
 }
 
 impl AccountSignature {
+    /// Returns the signature scheme of the signature.
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            AccountSignature::Ed25519(_) => SignatureScheme::Ed25519,
+            AccountSignature::Secp256k1(_) => SignatureScheme::Secp256k1,
+            AccountSignature::EvmSecp256k1(_) => SignatureScheme::EvmSecp256k1,
+        }
+    }
+
     /// Verifies the signature for the `value` using the provided `public_key`.
     pub fn verify<'de, T>(&self, value: &T, author: AccountPublicKey) -> Result<(), CryptoError>
     where
@@ -281,10 +295,29 @@ pub enum CryptoError {
     Secp256k1Error(k256::ecdsa::Error),
     #[error("could not parse public key: {0}: point at infinity")]
     Secp256k1PointAtInfinity(String),
+    #[error("could not parse public key: {0}: not a point on the curve")]
+    Secp256k1InvalidPoint(String),
     #[error("could not parse public key: {0}")]
     PublicKeyParseError(bcs::Error),
     #[error("could not parse signature: {0}")]
     SignatureParseError(bcs::Error),
+    #[error("key derivation produced an invalid secp256k1 key")]
+    InvalidKeyDerivation,
+    #[error("signature from {timestamp} is dated after the current time {now}")]
+    FutureSignature {
+        timestamp: Timestamp,
+        now: Timestamp,
+    },
+    #[error("signature from {timestamp} is older than {max_age:?} at {now}")]
+    ExpiredSignature {
+        timestamp: Timestamp,
+        now: Timestamp,
+        max_age: TimeDelta,
+    },
+    #[error("signer {0} appears more than once")]
+    DuplicateSigner(String),
+    #[error("only {valid} valid signatures but at least {threshold} are required")]
+    InsufficientValidSignatures { valid: usize, threshold: usize },
 }
 
 #[cfg(with_getrandom)]
@@ -361,6 +394,31 @@ where
     }
 }
 
+/// Returns the tag that [`CryptoHash::new`] writes before the serialized value of a `T`,
+/// e.g. to log which type a hash or signature covers.
+pub fn type_name_tag<'de, T: BcsHashable<'de>>() -> String {
+    format!("{}::", T::type_name())
+}
+
+/// Panics unless the hashes of `T` are separated from those of `U`, i.e. unless both type
+/// names are non-empty, don't contain the `::` separator that ends the tag, and differ.
+///
+/// Types with the same name in different modules, e.g. two `Vote`s, are not separated.
+#[cfg(with_testing)]
+pub fn assert_domain_separated<'de, T: BcsSignable<'de>, U: BcsSignable<'de>>() {
+    for name in [T::type_name(), U::type_name()] {
+        assert!(
+            !name.is_empty() && !name.contains("::"),
+            "the type name {name:?} does not end where the hashed value starts"
+        );
+    }
+    assert_ne!(
+        T::type_name(),
+        U::type_name(),
+        "both types have the same name, so their hashes are not separated"
+    );
+}
+
 /// A BCS-signable struct for testing.
 #[cfg(with_testing)]
 #[derive(Debug, Serialize, Deserialize)]
@@ -430,6 +488,108 @@ mod tests {
     use super::*;
     use crate::crypto::{ed25519::Ed25519SecretKey, secp256k1::Secp256k1KeyPair};
 
+    /// Panics if the `Debug` output of `value` contains the `secret` bytes, in hex or as a
+    /// list of numbers.
+    fn assert_no_secret_in<T: std::fmt::Debug>(value: &T, secret: &[u8]) {
+        let output = format!("{value:?} {value:#?}").to_lowercase();
+        let hex_secret = hex::encode(secret);
+        assert!(
+            !output.contains(&hex_secret),
+            "{output} contains the secret in hex"
+        );
+        let list_secret = format!("{secret:?}");
+        assert!(
+            !output.contains(&list_secret[1..list_secret.len() - 1]),
+            "{output} contains the secret bytes"
+        );
+    }
+
+    #[test]
+    fn test_secret_key_debug_is_redacted() {
+        let ed25519 = Ed25519SecretKey::generate();
+        let ed25519_bytes = ed25519.0.to_bytes();
+        let secp256k1 = Secp256k1KeyPair::generate().secret_key;
+        let secp256k1_bytes = secp256k1.0.to_bytes();
+        let evm = EvmSecretKey::generate();
+        let evm_bytes = evm.0.to_bytes();
+        assert_no_secret_in(&ed25519, &ed25519_bytes);
+        assert_no_secret_in(&secp256k1, &secp256k1_bytes);
+        assert_no_secret_in(&evm, &evm_bytes);
+
+        let keys = [
+            (AccountSecretKey::Ed25519(ed25519), &ed25519_bytes[..]),
+            (AccountSecretKey::Secp256k1(secp256k1), &secp256k1_bytes[..]),
+            (AccountSecretKey::EvmSecp256k1(evm), &evm_bytes[..]),
+        ];
+        for (key, bytes) in &keys {
+            assert_no_secret_in(key, bytes);
+            assert!(format!("{key:?}").contains("redacted"));
+        }
+    }
+
+    #[test]
+    fn test_secret_key_equality() {
+        let keys = [
+            AccountSecretKey::Ed25519(Ed25519SecretKey::generate()),
+            AccountSecretKey::Secp256k1(Secp256k1KeyPair::generate().secret_key),
+            AccountSecretKey::EvmSecp256k1(EvmSecretKey::generate()),
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(*key, key.copy());
+            for (j, other) in keys.iter().enumerate() {
+                assert_eq!(i == j, key == other);
+            }
+        }
+        assert_ne!(
+            AccountSecretKey::Ed25519(Ed25519SecretKey::generate()),
+            AccountSecretKey::Ed25519(Ed25519SecretKey::generate())
+        );
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo(String);
+        #[derive(Serialize, Deserialize)]
+        struct Bar(String);
+
+        impl BcsSignable<'_> for Foo {}
+        impl BcsSignable<'_> for Bar {}
+
+        assert_domain_separated::<Foo, Bar>();
+        assert_domain_separated::<Foo, TestString>();
+        assert_eq!(type_name_tag::<Foo>(), "Foo::");
+        assert_eq!(
+            bcs::to_bytes(&Foo("hello".into())).unwrap(),
+            bcs::to_bytes(&Bar("hello".into())).unwrap()
+        );
+        assert_ne!(
+            CryptoHash::new(&Foo("hello".into())),
+            CryptoHash::new(&Bar("hello".into()))
+        );
+    }
+
+    #[test]
+    fn test_domain_separation_same_name() {
+        mod a {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            pub struct Vote(pub String);
+            impl crate::crypto::BcsSignable<'_> for Vote {}
+        }
+        mod b {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            pub struct Vote(pub String);
+            impl crate::crypto::BcsSignable<'_> for Vote {}
+        }
+
+        assert_eq!(
+            CryptoHash::new(&a::Vote("yes".into())),
+            CryptoHash::new(&b::Vote("yes".into()))
+        );
+        let result = std::panic::catch_unwind(assert_domain_separated::<a::Vote, b::Vote>);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_u64_array_to_be_bytes() {
         let input = [
@@ -497,6 +657,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_signature_scheme() {
+        fn test(secret: AccountSecretKey, scheme: SignatureScheme) {
+            let signature = secret.sign(&TestString::new("test"));
+            assert_eq!(signature.scheme(), scheme);
+            assert_eq!(secret.public().scheme(), scheme);
+        }
+        test(
+            AccountSecretKey::Ed25519(Ed25519SecretKey::generate()),
+            SignatureScheme::Ed25519,
+        );
+        test(
+            AccountSecretKey::Secp256k1(Secp256k1KeyPair::generate().secret_key),
+            SignatureScheme::Secp256k1,
+        );
+        test(
+            AccountSecretKey::EvmSecp256k1(EvmSecretKey::generate()),
+            SignatureScheme::EvmSecp256k1,
+        );
+    }
+
     #[test]
     fn roundtrip_display_from_str_pk() {
         fn test(secret: AccountSecretKey) {
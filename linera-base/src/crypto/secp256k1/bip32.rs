@@ -0,0 +1,217 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Defines BIP-32 hierarchical derivation of secp256k1 secret keys.
+
+use hmac::{Hmac, Mac};
+use k256::{
+    ecdsa::SigningKey,
+    elliptic_curve::{zeroize::Zeroizing, PrimeField},
+    sha2::Sha512,
+    FieldBytes, Scalar,
+};
+
+use super::Secp256k1SecretKey;
+use crate::crypto::CryptoError;
+
+/// Child indices starting from this value use hardened derivation.
+pub const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// The HMAC key used to derive the master key from a seed.
+const MASTER_KEY_HMAC_KEY: &[u8] = b"Bitcoin seed";
+
+/// A secp256k1 secret key together with the chain code needed to derive child keys.
+pub struct ExtendedSecp256k1SecretKey {
+    secret_key: Secp256k1SecretKey,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedSecp256k1SecretKey {
+    /// Derives the master key from the given seed.
+    pub fn from_seed(seed: &[u8]) -> Result<Self, CryptoError> {
+        let output = hmac_sha512(MASTER_KEY_HMAC_KEY, &[seed]);
+        Self::from_hmac_output(&output, None)
+    }
+
+    /// Derives the child key with the given index. Indices of at least [`HARDENED_OFFSET`]
+    /// use hardened derivation.
+    pub fn derive_child(&self, index: u32) -> Result<Self, CryptoError> {
+        let output = if index >= HARDENED_OFFSET {
+            let secret_bytes = Zeroizing::new(self.secret_key.0.to_bytes());
+            hmac_sha512(
+                &self.chain_code[..],
+                &[&[0], &secret_bytes[..], &index.to_be_bytes()],
+            )
+        } else {
+            let public_bytes = self.secret_key.public().as_bytes();
+            hmac_sha512(&self.chain_code[..], &[&public_bytes, &index.to_be_bytes()])
+        };
+        Self::from_hmac_output(&output, Some(&self.secret_key))
+    }
+
+    /// Derives the key at the given path of child indices.
+    pub fn derive_path(&self, path: &[u32]) -> Result<Self, CryptoError> {
+        let mut key = self.copy();
+        for index in path {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    /// Returns the secret key.
+    pub fn secret_key(&self) -> &Secp256k1SecretKey {
+        &self.secret_key
+    }
+
+    /// Returns the chain code.
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Copies the extended key, **including the secret key**.
+    pub fn copy(&self) -> Self {
+        ExtendedSecp256k1SecretKey {
+            secret_key: self.secret_key.copy(),
+            chain_code: self.chain_code.clone(),
+        }
+    }
+
+    /// Builds a key from the output of HMAC-SHA512: the left half is the key, or the tweak
+    /// to add to the `parent` key, and the right half is the chain code.
+    fn from_hmac_output(
+        output: &[u8; 64],
+        parent: Option<&Secp256k1SecretKey>,
+    ) -> Result<Self, CryptoError> {
+        let (left, right) = output.split_at(32);
+        let mut tweak_bytes = Zeroizing::new(FieldBytes::default());
+        tweak_bytes.copy_from_slice(left);
+        let tweak = Option::<Scalar>::from(Scalar::from_repr(*tweak_bytes))
+            .ok_or(CryptoError::InvalidKeyDerivation)?;
+        let scalar = match parent {
+            Some(parent) => tweak + parent.0.as_nonzero_scalar().as_ref(),
+            None => tweak,
+        };
+        let key_bytes = Zeroizing::new(scalar.to_bytes());
+        let signing_key =
+            SigningKey::from_bytes(&key_bytes).map_err(|_| CryptoError::InvalidKeyDerivation)?;
+        let mut chain_code = Zeroizing::new([0u8; 32]);
+        chain_code.copy_from_slice(right);
+        Ok(ExtendedSecp256k1SecretKey {
+            secret_key: Secp256k1SecretKey(signing_key),
+            chain_code,
+        })
+    }
+}
+
+/// Computes HMAC-SHA512 of the concatenation of `data` with the given key.
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> Zeroizing<[u8; 64]> {
+    // UNWRAP: HMAC accepts keys of any length.
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+    for chunk in data {
+        mac.update(chunk);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtendedSecp256k1SecretKey, HARDENED_OFFSET};
+
+    fn assert_key(key: &ExtendedSecp256k1SecretKey, chain_code: &str, secret_key: &str) {
+        assert_eq!(hex::encode(key.chain_code()), chain_code);
+        assert_eq!(hex::encode(key.secret_key().0.to_bytes()), secret_key);
+    }
+
+    /// Test vector 1 from BIP-32.
+    #[test]
+    fn test_bip32_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedSecp256k1SecretKey::from_seed(&seed).unwrap();
+        assert_key(
+            &master,
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+        );
+        let child = master.derive_child(HARDENED_OFFSET).unwrap();
+        assert_key(
+            &child,
+            "47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141",
+            "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+        );
+        let child = child.derive_child(1).unwrap();
+        assert_key(
+            &child,
+            "2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19",
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+        );
+        let child = child.derive_child(HARDENED_OFFSET + 2).unwrap();
+        assert_key(
+            &child,
+            "04466b9cc8e161e966409ca52986c584f07e9dc81f735db683c3ff6ec7b1503f",
+            "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca",
+        );
+
+        let path_child = master
+            .derive_path(&[HARDENED_OFFSET, 1, HARDENED_OFFSET + 2])
+            .unwrap();
+        assert_eq!(path_child.secret_key(), child.secret_key());
+        assert_eq!(path_child.chain_code(), child.chain_code());
+        let same = master.derive_path(&[]).unwrap();
+        assert_eq!(same.secret_key(), master.secret_key());
+    }
+
+    /// Test vector 2 from BIP-32.
+    #[test]
+    fn test_bip32_vector_2() {
+        let seed = hex::decode(
+            "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a87\
+             84817e7b7875726f6c696663605d5a5754514e4b484542",
+        )
+        .unwrap();
+        let master = ExtendedSecp256k1SecretKey::from_seed(&seed).unwrap();
+        assert_key(
+            &master,
+            "60499f801b896d83179a4374aeb7822aaeaceaa0db1f85ee3e904c4defbd9689",
+            "4b03d6fc340455b363f51020ad3ecca4f0850280cf436c70c727923f6db46c3e",
+        );
+        let path = [
+            0,
+            HARDENED_OFFSET + 2147483647,
+            1,
+            HARDENED_OFFSET + 2147483646,
+            2,
+        ];
+        let expected = [
+            (
+                "f0909affaa7ee7abe5dd4e100598d4dc53cd709d5a5c2cac40e7412f232f7c9c",
+                "abe74a98f6c7eabee0428f53798f0ab8aa1bd37873999041703c742f15ac7e1e",
+            ),
+            (
+                "be17a268474a6bb9c61e1d720cf6215e2a88c5406c4aee7b38547f585c9a37d9",
+                "877c779ad9687164e9c2f4f0f4ff0340814392330693ce95a58fe18fd52e6e93",
+            ),
+            (
+                "f366f48f1ea9f2d1d3fe958c95ca84ea18e4c4ddb9366c336c927eb246fb38cb",
+                "704addf544a06e5ee4bea37098463c23613da32020d604506da8c0518e1da4b7",
+            ),
+            (
+                "637807030d55d01f9a0cb3a7839515d796bd07706386a6eddf06cc29a65a0e29",
+                "f1c7c871a54a804afe328b4c83a1c33b8e5ff48f5087273f04efa83b247d6a2d",
+            ),
+            (
+                "9452b549be8cea3ecb7a84bec10dcfd94afe4d129ebfd3b3cb58eedf394ed271",
+                "bb7d39bdb83ecf58f2fd82b6d918341cbef428661ef01ab97c28a4842125ac23",
+            ),
+        ];
+        let mut key = master;
+        for (index, (chain_code, secret_key)) in path.into_iter().zip(expected) {
+            key = key.derive_child(index).unwrap();
+            assert_key(&key, chain_code, secret_key);
+        }
+        let path_key = ExtendedSecp256k1SecretKey::from_seed(&seed)
+            .unwrap()
+            .derive_path(&path)
+            .unwrap();
+        assert_eq!(path_key.secret_key(), key.secret_key());
+    }
+}
@@ -40,7 +40,8 @@ pub struct EvmSecretKey(pub SigningKey);
 impl Eq for EvmSecretKey {}
 impl PartialEq for EvmSecretKey {
     fn eq(&self, other: &Self) -> bool {
-        self.0.to_bytes() == other.0.to_bytes()
+        // Compares the scalars in constant time.
+        self.0 == other.0
     }
 }
 
@@ -255,7 +256,8 @@ impl WitLoad for EvmPublicKey {
         <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
     {
         let (part1, part2, part3, part4, part5) = WitLoad::load(memory, location)?;
-        Ok(Self::from((part1, part2, part3, part4, part5)))
+        Self::try_from((part1, part2, part3, part4, part5))
+            .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
     fn lift_from<Instance>(
@@ -267,7 +269,8 @@ impl WitLoad for EvmPublicKey {
         <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
     {
         let (part1, part2, part3, part4, part5) = WitLoad::lift_from(flat_layout, memory)?;
-        Ok(Self::from((part1, part2, part3, part4, part5)))
+        Self::try_from((part1, part2, part3, part4, part5))
+            .map_err(|error| RuntimeError::Custom(error.into()))
     }
 }
 
@@ -298,15 +301,19 @@ impl WitStore for EvmPublicKey {
     }
 }
 
-impl From<(u64, u64, u64, u64, u8)> for EvmPublicKey {
-    fn from((part1, part2, part3, part4, part5): (u64, u64, u64, u64, u8)) -> Self {
+impl TryFrom<(u64, u64, u64, u64, u8)> for EvmPublicKey {
+    type Error = CryptoError;
+
+    fn try_from(
+        (part1, part2, part3, part4, part5): (u64, u64, u64, u64, u8),
+    ) -> Result<Self, Self::Error> {
         let mut bytes = [0u8; EVM_SECP256K1_PUBLIC_KEY_SIZE];
         bytes[0..8].copy_from_slice(&part1.to_be_bytes());
         bytes[8..16].copy_from_slice(&part2.to_be_bytes());
         bytes[16..24].copy_from_slice(&part3.to_be_bytes());
         bytes[24..32].copy_from_slice(&part4.to_be_bytes());
         bytes[32] = part5;
-        Self::from_bytes(&bytes).unwrap()
+        Self::from_bytes(&bytes)
     }
 }
 
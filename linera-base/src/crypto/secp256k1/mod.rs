@@ -4,18 +4,24 @@
 
 //! Defines secp256k1 signature primitives used by the Linera protocol.
 
+pub mod bip32;
 pub mod evm;
+pub mod schnorr;
 
 use std::{
     borrow::Cow,
+    collections::HashSet,
     fmt,
     hash::{Hash, Hasher},
     str::FromStr,
 };
 
 use k256::{
-    ecdsa::{Signature, SigningKey, VerifyingKey},
-    elliptic_curve::sec1::FromEncodedPoint,
+    ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey},
+    elliptic_curve::{
+        sec1::FromEncodedPoint,
+        zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing},
+    },
     EncodedPoint,
 };
 use linera_witty::{
@@ -25,7 +31,10 @@ use linera_witty::{
 use serde::{Deserialize, Serialize};
 
 use super::{BcsHashable, BcsSignable, CryptoError, CryptoHash, HasTypeName};
-use crate::doc_scalar;
+use crate::{
+    data_types::{TimeDelta, Timestamp},
+    doc_scalar,
+};
 
 /// Name of the secp256k1 scheme.
 const SECP256K1_SCHEME_LABEL: &str = "secp256k1";
@@ -33,13 +42,24 @@ const SECP256K1_SCHEME_LABEL: &str = "secp256k1";
 /// Length of secp256k1 compressed public key.
 const SECP256K1_PUBLIC_KEY_SIZE: usize = 33;
 
+/// Length of secp256k1 uncompressed public key.
+const SECP256K1_UNCOMPRESSED_PUBLIC_KEY_SIZE: usize = 65;
+
 /// Length of secp256k1 signature.
 const SECP256K1_SIGNATURE_SIZE: usize = 64;
 
+/// Length of secp256k1 recoverable signature: the signature followed by the recovery ID.
+const SECP256K1_RECOVERABLE_SIGNATURE_SIZE: usize = SECP256K1_SIGNATURE_SIZE + 1;
+
 /// A secp256k1 secret key.
+///
+/// The key material is overwritten when the key is dropped.
 #[derive(Eq, PartialEq)]
 pub struct Secp256k1SecretKey(pub SigningKey);
 
+// The wrapped `SigningKey` zeroizes its scalar when dropped.
+impl ZeroizeOnDrop for Secp256k1SecretKey {}
+
 /// A secp256k1 public key.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct Secp256k1PublicKey(pub VerifyingKey);
@@ -63,6 +83,43 @@ pub struct Secp256k1KeyPair {
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub struct Secp256k1Signature(pub Signature);
 
+/// Secp256k1 signatures by several signers on the same value, in the order they were given.
+///
+/// This is not an aggregate signature in the BLS sense: each signature is kept and checked
+/// individually, but the value is hashed only once.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AggregatedSecp256k1 {
+    signatures: Vec<(Secp256k1PublicKey, Secp256k1Signature)>,
+}
+
+/// A secp256k1 signature on a value together with the time it was created, so that it can
+/// be rejected once it is too old.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TimestampedSecp256k1Signature {
+    /// The signature on the hash of the value and the timestamp.
+    pub signature: Secp256k1Signature,
+    /// The time at which the signature was created.
+    pub timestamp: Timestamp,
+}
+
+/// The data actually signed by a [`TimestampedSecp256k1Signature`].
+#[derive(Debug, Serialize, Deserialize)]
+struct TimestampedValue {
+    value_hash: CryptoHash,
+    timestamp: Timestamp,
+}
+
+impl BcsSignable<'_> for TimestampedValue {}
+
+/// A secp256k1 signature from which the signer's public key can be recovered.
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct Secp256k1RecoverableSignature {
+    /// The signature.
+    pub signature: Signature,
+    /// The ID needed to recover the public key from the signature.
+    pub recovery_id: RecoveryId,
+}
+
 impl Secp256k1PublicKey {
     /// A fake public key used for testing.
     #[cfg(all(with_testing, not(target_arch = "wasm32")))]
@@ -79,26 +136,87 @@ impl Secp256k1PublicKey {
         self.0.to_encoded_point(true).as_bytes().try_into().unwrap()
     }
 
+    /// Returns whether the two keys are equal, in constant time.
+    ///
+    /// Public keys are not secret, but comparing a provided key against an expected signer
+    /// with `==` can reveal through timing how many leading bytes matched.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        use k256::elliptic_curve::subtle::ConstantTimeEq as _;
+
+        self.as_bytes().ct_eq(&other.as_bytes()).into()
+    }
+
     /// Decodes the bytes into the public key.
     /// Expects the bytes to be of compressed representation.
     ///
     /// Panics if the encoding can't be done in a constant time.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
-        let encoded_point =
-            EncodedPoint::from_bytes(bytes).map_err(|_| CryptoError::IncorrectPublicKeySize {
-                scheme: SECP256K1_SCHEME_LABEL,
-                len: bytes.len(),
-                expected: SECP256K1_PUBLIC_KEY_SIZE,
-            })?;
+        let encoded_point = EncodedPoint::from_bytes(bytes).map_err(|_| {
+            if bytes.len() == SECP256K1_PUBLIC_KEY_SIZE {
+                // The length is right but the tag byte is not.
+                CryptoError::Secp256k1InvalidPoint(hex::encode(bytes))
+            } else {
+                CryptoError::IncorrectPublicKeySize {
+                    scheme: SECP256K1_SCHEME_LABEL,
+                    len: bytes.len(),
+                    expected: SECP256K1_PUBLIC_KEY_SIZE,
+                }
+            }
+        })?;
 
         match k256::PublicKey::from_encoded_point(&encoded_point).into_option() {
             Some(public_key) => Ok(Self(public_key.into())),
-            None => {
-                let error = CryptoError::Secp256k1PointAtInfinity(hex::encode(bytes));
-                Err(error)
+            None if encoded_point.is_identity() => {
+                Err(CryptoError::Secp256k1PointAtInfinity(hex::encode(bytes)))
             }
+            None => Err(CryptoError::Secp256k1InvalidPoint(hex::encode(bytes))),
         }
     }
+
+    /// Returns the bytes of the public key in uncompressed representation, as used by
+    /// Ethereum tooling.
+    pub fn to_uncompressed(&self) -> [u8; SECP256K1_UNCOMPRESSED_PUBLIC_KEY_SIZE] {
+        // UNWRAP: We already have valid key so conversion should not fail.
+        self.0
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Decodes the public key from its uncompressed representation.
+    pub fn from_uncompressed(
+        bytes: &[u8; SECP256K1_UNCOMPRESSED_PUBLIC_KEY_SIZE],
+    ) -> Result<Self, CryptoError> {
+        // The length is fixed, so a bad tag byte is the only way the encoding can be wrong.
+        EncodedPoint::from_bytes(bytes)
+            .ok()
+            .filter(|point| !point.is_compressed())
+            .and_then(|point| k256::PublicKey::from_encoded_point(&point).into_option())
+            .map(|public_key| Self(public_key.into()))
+            .ok_or_else(|| CryptoError::Secp256k1InvalidPoint(hex::encode(bytes)))
+    }
+
+    /// Checks that `signature` is a valid signature of `value` by this key. This is the same
+    /// as [`Secp256k1Signature::check`].
+    pub fn verify<'de, T>(
+        &self,
+        value: &T,
+        signature: &Secp256k1Signature,
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        signature.check(value, self)
+    }
+
+    /// Returns the Ethereum address of the public key: the last 20 bytes of the Keccak-256
+    /// hash of the uncompressed key, without its `0x04` prefix.
+    pub fn eth_address(&self) -> [u8; 20] {
+        let hash = alloy_primitives::keccak256(&self.to_uncompressed()[1..]);
+        // UNWRAP: A Keccak-256 hash has 32 bytes.
+        hash[12..].try_into().unwrap()
+    }
 }
 
 impl fmt::Debug for Secp256k1SecretKey {
@@ -107,14 +225,25 @@ impl fmt::Debug for Secp256k1SecretKey {
     }
 }
 
+/// Serializes the secret key as a hex string in human-readable formats such as JSON.
+///
+/// **Warning**: Binary formats are only supported with the `with-unsafe-secret-serialization`
+/// feature, and panic otherwise. Binary encodings of secret keys are easy to store, log or
+/// send by accident, so only enable the feature in tooling that deliberately handles raw keys.
 impl Serialize for Secp256k1SecretKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        // This is only used for JSON configuration.
+        #[cfg(not(with_unsafe_secret_serialization))]
         assert!(serializer.is_human_readable());
-        serializer.serialize_str(&hex::encode(self.0.to_bytes()))
+        let bytes = Zeroizing::new(self.0.to_bytes());
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&Zeroizing::new(hex::encode(&bytes[..])))
+        } else {
+            let bytes = Zeroizing::new(<[u8; 32]>::from(*bytes));
+            serializer.serialize_newtype_struct("Secp256k1SecretKey", &*bytes)
+        }
     }
 }
 
@@ -123,10 +252,21 @@ impl<'de> Deserialize<'de> for Secp256k1SecretKey {
     where
         D: serde::de::Deserializer<'de>,
     {
-        // This is only used for JSON configuration.
+        #[cfg(not(with_unsafe_secret_serialization))]
         assert!(deserializer.is_human_readable());
-        let str = String::deserialize(deserializer)?;
-        let bytes = hex::decode(&str).map_err(serde::de::Error::custom)?;
+        let bytes = if deserializer.is_human_readable() {
+            let str = Zeroizing::new(String::deserialize(deserializer)?);
+            Zeroizing::new(hex::decode(&*str).map_err(serde::de::Error::custom)?)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "Secp256k1SecretKey")]
+            struct SecretKey([u8; 32]);
+
+            let mut value = SecretKey::deserialize(deserializer)?;
+            let bytes = Zeroizing::new(value.0.to_vec());
+            value.0.zeroize();
+            bytes
+        };
         let sk = k256::ecdsa::SigningKey::from_slice(&bytes).map_err(serde::de::Error::custom)?;
         Ok(Secp256k1SecretKey(sk))
     }
@@ -229,7 +369,8 @@ impl WitLoad for Secp256k1PublicKey {
         <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
     {
         let (part1, part2, part3, part4, part5) = WitLoad::load(memory, location)?;
-        Ok(Self::from((part1, part2, part3, part4, part5)))
+        Self::try_from((part1, part2, part3, part4, part5))
+            .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
     fn lift_from<Instance>(
@@ -241,7 +382,8 @@ impl WitLoad for Secp256k1PublicKey {
         <Instance::Runtime as Runtime>::Memory: RuntimeMemory<Instance>,
     {
         let (part1, part2, part3, part4, part5) = WitLoad::lift_from(flat_layout, memory)?;
-        Ok(Self::from((part1, part2, part3, part4, part5)))
+        Self::try_from((part1, part2, part3, part4, part5))
+            .map_err(|error| RuntimeError::Custom(error.into()))
     }
 }
 
@@ -272,15 +414,19 @@ impl WitStore for Secp256k1PublicKey {
     }
 }
 
-impl From<(u64, u64, u64, u64, u8)> for Secp256k1PublicKey {
-    fn from((part1, part2, part3, part4, part5): (u64, u64, u64, u64, u8)) -> Self {
+impl TryFrom<(u64, u64, u64, u64, u8)> for Secp256k1PublicKey {
+    type Error = CryptoError;
+
+    fn try_from(
+        (part1, part2, part3, part4, part5): (u64, u64, u64, u64, u8),
+    ) -> Result<Self, Self::Error> {
         let mut bytes = [0u8; SECP256K1_PUBLIC_KEY_SIZE];
         bytes[0..8].copy_from_slice(&part1.to_be_bytes());
         bytes[8..16].copy_from_slice(&part2.to_be_bytes());
         bytes[16..24].copy_from_slice(&part3.to_be_bytes());
         bytes[24..32].copy_from_slice(&part4.to_be_bytes());
         bytes[32] = part5;
-        Self::from_bytes(&bytes).unwrap()
+        Self::from_bytes(&bytes)
     }
 }
 
@@ -297,6 +443,17 @@ impl From<Secp256k1PublicKey> for (u64, u64, u64, u64, u8) {
 }
 
 impl Secp256k1KeyPair {
+    /// Builds a key pair from the raw bytes of a secret key, deriving the public key.
+    pub fn from_secret_bytes(bytes: &[u8; 32]) -> Result<Self, CryptoError> {
+        let secret_key =
+            Secp256k1SecretKey(SigningKey::from_slice(bytes).map_err(CryptoError::Secp256k1Error)?);
+        let public_key = secret_key.public();
+        Ok(Secp256k1KeyPair {
+            secret_key,
+            public_key,
+        })
+    }
+
     /// Generates a new key pair.
     #[cfg(all(with_getrandom, with_testing))]
     pub fn generate() -> Self {
@@ -314,6 +471,14 @@ impl Secp256k1KeyPair {
             public_key,
         }
     }
+
+    /// Signs `value` with the secret key. This is the same as [`Secp256k1Signature::new`].
+    pub fn sign<'de, T>(&self, value: &T) -> Secp256k1Signature
+    where
+        T: BcsSignable<'de>,
+    {
+        Secp256k1Signature::new(value, &self.secret_key)
+    }
 }
 
 impl Secp256k1SecretKey {
@@ -351,28 +516,55 @@ impl Secp256k1Signature {
     where
         T: BcsSignable<'de>,
     {
+        Self::sign_prehash(&CryptoHash::new(value), secret)
+    }
+
+    /// Computes a secp256k1 signature for a `hash` that was computed elsewhere.
+    ///
+    /// **Warning**: Unlike [`Secp256k1Signature::new`], this does not guarantee that the
+    /// signed bytes start with the type name of a signable value. The caller must make sure
+    /// that the hash cannot be confused with the hash of another value.
+    pub fn sign_prehash(hash: &CryptoHash, secret: &Secp256k1SecretKey) -> Self {
         use k256::ecdsa::signature::hazmat::PrehashSigner;
 
-        let prehash = CryptoHash::new(value).as_bytes().0;
         let (signature, _rid) = secret
             .0
-            .sign_prehash(&prehash)
+            .sign_prehash(&hash.as_bytes().0)
             .expect("Failed to sign prehashed data"); // NOTE: This is a critical error we don't control.
-        Secp256k1Signature(signature)
+
+        // Only the low-S form is accepted by `check`, so that signatures are not malleable.
+        Secp256k1Signature(signature.normalize_s().unwrap_or(signature))
     }
 
-    /// Checks a signature.
+    /// Checks a signature. Signatures that are not in low-S form are rejected.
     pub fn check<'de, T>(&self, value: &T, author: &Secp256k1PublicKey) -> Result<(), CryptoError>
     where
         T: BcsSignable<'de> + fmt::Debug,
     {
         let prehash = CryptoHash::new(value).as_bytes().0;
-        self.verify_inner::<T>(prehash, author)
+        self.verify_inner(prehash, author, T::type_name())
+    }
+
+    /// Checks a signature of a `hash` that was computed elsewhere, as created by
+    /// [`Secp256k1Signature::sign_prehash`]. Signatures that are not in low-S form are
+    /// rejected.
+    pub fn check_prehash(
+        &self,
+        hash: &CryptoHash,
+        author: &Secp256k1PublicKey,
+    ) -> Result<(), CryptoError> {
+        self.verify_inner(hash.as_bytes().0, author, "CryptoHash")
     }
 
     /// Verifies a batch of signatures.
     ///
-    /// Returns an error on first failed signature.
+    /// Returns an error on first failed signature. Like [`Secp256k1Signature::check`], this
+    /// rejects signatures that are not in low-S form.
+    ///
+    /// Unlike Ed25519 or Schnorr signatures, ECDSA signatures cannot be batch-verified:
+    /// a signature only stores the x-coordinate of its nonce point, so the verification
+    /// equations cannot be combined. The value is hashed once and each signature is then
+    /// checked individually.
     pub fn verify_batch<'a, 'de, T, I>(value: &'a T, votes: I) -> Result<(), CryptoError>
     where
         T: BcsSignable<'de> + fmt::Debug,
@@ -380,32 +572,132 @@ impl Secp256k1Signature {
     {
         let prehash = CryptoHash::new(value).as_bytes().0;
         for (author, signature) in votes {
-            signature.verify_inner::<T>(prehash, author)?;
+            signature.verify_inner(prehash, author, T::type_name())?;
+        }
+        Ok(())
+    }
+
+    /// Verifies a batch of signatures like [`Secp256k1Signature::verify_batch`], but checks
+    /// all of them. On failure, returns the indices of all invalid signatures, so that the
+    /// faulty signers can be identified.
+    pub fn verify_batch_detailed<'a, 'de, T, I>(value: &'a T, votes: I) -> Result<(), Vec<usize>>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+        I: IntoIterator<Item = &'a (Secp256k1PublicKey, Secp256k1Signature)>,
+    {
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        let invalid_indices = votes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (author, signature))| {
+                signature
+                    .verify_inner(prehash, author, T::type_name())
+                    .is_err()
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if invalid_indices.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_indices)
+        }
+    }
+
+    /// Verifies a batch of signatures, each on its own value and from its own signer.
+    /// On failure, returns the index of the first invalid signature.
+    ///
+    /// The secp256k1 backend has no randomized batch verification, so the signatures are
+    /// checked one at a time.
+    pub fn verify_heterogeneous_batch<'a, 'de, T, I>(items: I) -> Result<(), (usize, CryptoError)>
+    where
+        T: BcsSignable<'de> + fmt::Debug + 'a,
+        I: IntoIterator<Item = (&'a T, &'a Secp256k1PublicKey, &'a Secp256k1Signature)>,
+    {
+        for (index, (value, author, signature)) in items.into_iter().enumerate() {
+            let prehash = CryptoHash::new(value).as_bytes().0;
+            signature
+                .verify_inner(prehash, author, T::type_name())
+                .map_err(|error| (index, error))?;
+        }
+        Ok(())
+    }
+
+    /// Verifies that at least `threshold` of the signatures on the same value are valid,
+    /// stopping as soon as enough valid signatures have been found. Each signer may only
+    /// appear once, so that a repeated vote cannot be counted twice.
+    ///
+    /// With the `rayon` feature, the signatures are verified in parallel.
+    pub fn verify_quorum<'de, T>(
+        value: &T,
+        votes: &[(Secp256k1PublicKey, Secp256k1Signature)],
+        threshold: usize,
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        let mut signers = HashSet::new();
+        for (author, _) in votes {
+            if !signers.insert(author) {
+                return Err(CryptoError::DuplicateSigner(author.to_string()));
+            }
+        }
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        let is_valid = |(author, signature): &&(Secp256k1PublicKey, Secp256k1Signature)| {
+            signature
+                .verify_inner(prehash, author, T::type_name())
+                .is_ok()
+        };
+        #[cfg(with_rayon)]
+        let valid = {
+            use rayon::prelude::*;
+            votes
+                .par_iter()
+                .filter(is_valid)
+                .take_any(threshold)
+                .count()
+        };
+        #[cfg(not(with_rayon))]
+        let valid = votes.iter().filter(is_valid).take(threshold).count();
+        if valid < threshold {
+            return Err(CryptoError::InsufficientValidSignatures { valid, threshold });
         }
         Ok(())
     }
 
+    /// Bundles the signatures of several signers on the same value, keeping their order.
+    pub fn aggregate(
+        signatures: &[(Secp256k1PublicKey, Secp256k1Signature)],
+    ) -> AggregatedSecp256k1 {
+        AggregatedSecp256k1 {
+            signatures: signatures.to_vec(),
+        }
+    }
+
     /// Returns the byte representation of the signature.
     pub fn as_bytes(&self) -> [u8; SECP256K1_SIGNATURE_SIZE] {
         self.0.to_bytes().into()
     }
 
-    fn verify_inner<'de, T>(
+    fn verify_inner(
         &self,
         prehash: [u8; 32],
         author: &Secp256k1PublicKey,
-    ) -> Result<(), CryptoError>
-    where
-        T: BcsSignable<'de> + fmt::Debug,
-    {
+        type_name: &str,
+    ) -> Result<(), CryptoError> {
         use k256::ecdsa::signature::hazmat::PrehashVerifier;
 
+        if self.0.normalize_s().is_some() {
+            return Err(CryptoError::InvalidSignature {
+                error: "signature is not normalized to low-S form".to_string(),
+                type_name: type_name.to_string(),
+            });
+        }
         author
             .0
             .verify_prehash(&prehash, &self.0)
             .map_err(|error| CryptoError::InvalidSignature {
                 error: error.to_string(),
-                type_name: T::type_name().to_string(),
+                type_name: type_name.to_string(),
             })
     }
 
@@ -416,6 +708,225 @@ impl Secp256k1Signature {
             .map_err(CryptoError::Secp256k1Error)?;
         Ok(Secp256k1Signature(sig))
     }
+
+    /// Returns the fixed-size compact encoding of the signature, which is also used by the
+    /// binary serialization.
+    pub fn to_compact(&self) -> [u8; SECP256K1_SIGNATURE_SIZE] {
+        self.as_bytes()
+    }
+
+    /// Creates a signature from its compact encoding.
+    pub fn from_compact(bytes: &[u8; SECP256K1_SIGNATURE_SIZE]) -> Result<Self, CryptoError> {
+        Self::from_slice(bytes)
+    }
+
+    /// Returns the variable-length DER encoding of the signature, as expected by some
+    /// external tooling.
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.to_der().as_bytes().to_vec()
+    }
+
+    /// Creates a signature from its DER encoding.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let sig = Signature::from_der(bytes).map_err(CryptoError::Secp256k1Error)?;
+        Ok(Secp256k1Signature(sig))
+    }
+}
+
+impl Secp256k1RecoverableSignature {
+    /// Computes a recoverable secp256k1 signature for `value` using the given `secret`.
+    pub fn new_recoverable<'de, T>(value: &T, secret: &Secp256k1SecretKey) -> Self
+    where
+        T: BcsSignable<'de>,
+    {
+        use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        let (signature, recovery_id) = secret
+            .0
+            .sign_prehash(&prehash)
+            .expect("Failed to sign prehashed data"); // NOTE: This is a critical error we don't control.
+        Secp256k1RecoverableSignature {
+            signature,
+            recovery_id,
+        }
+    }
+
+    /// Recovers the public key that signed `value`.
+    pub fn recover<'de, T>(&self, value: &T) -> Result<Secp256k1PublicKey, CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        VerifyingKey::recover_from_prehash(&prehash, &self.signature, self.recovery_id)
+            .map(Secp256k1PublicKey)
+            .map_err(|error| CryptoError::InvalidSignature {
+                error: error.to_string(),
+                type_name: T::type_name().to_string(),
+            })
+    }
+
+    /// Checks that this signature was created for `value` by `author`.
+    pub fn check<'de, T>(&self, value: &T, author: &Secp256k1PublicKey) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        Secp256k1Signature(self.signature).check(value, author)
+    }
+
+    /// Returns the byte representation of the signature, followed by the recovery ID.
+    pub fn as_bytes(&self) -> [u8; SECP256K1_RECOVERABLE_SIGNATURE_SIZE] {
+        let mut bytes = [0u8; SECP256K1_RECOVERABLE_SIGNATURE_SIZE];
+        bytes[..SECP256K1_SIGNATURE_SIZE].copy_from_slice(&self.signature.to_bytes());
+        bytes[SECP256K1_SIGNATURE_SIZE] = self.recovery_id.to_byte();
+        bytes
+    }
+
+    /// Creates a recoverable signature from the bytes.
+    /// Expects the signature in raw-bytes form, followed by the recovery ID.
+    pub fn from_slice<A: AsRef<[u8]>>(bytes: A) -> Result<Self, CryptoError> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != SECP256K1_RECOVERABLE_SIGNATURE_SIZE {
+            return Err(CryptoError::IncorrectSignatureBytes {
+                scheme: SECP256K1_SCHEME_LABEL,
+                len: bytes.len(),
+                expected: SECP256K1_RECOVERABLE_SIGNATURE_SIZE,
+            });
+        }
+        let (signature, recovery_id) = bytes.split_at(SECP256K1_SIGNATURE_SIZE);
+        let signature = Signature::from_slice(signature).map_err(CryptoError::Secp256k1Error)?;
+        let recovery_id = RecoveryId::from_byte(recovery_id[0])
+            .ok_or(CryptoError::Secp256k1Error(k256::ecdsa::Error::new()))?;
+        Ok(Secp256k1RecoverableSignature {
+            signature,
+            recovery_id,
+        })
+    }
+}
+
+impl AggregatedSecp256k1 {
+    /// Checks that all signatures are valid signatures of `value` by their signers.
+    pub fn verify<'de, T>(&self, value: &T) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        Secp256k1Signature::verify_batch(value, &self.signatures)
+    }
+
+    /// Returns the public keys of the signers, in order.
+    pub fn signers(&self) -> impl Iterator<Item = &Secp256k1PublicKey> {
+        self.signatures.iter().map(|(public_key, _)| public_key)
+    }
+
+    /// Returns the signers and their signatures, in order.
+    pub fn signatures(&self) -> &[(Secp256k1PublicKey, Secp256k1Signature)] {
+        &self.signatures
+    }
+}
+
+impl TimestampedSecp256k1Signature {
+    /// Signs `value` together with the given `timestamp`.
+    pub fn new<'de, T>(value: &T, timestamp: Timestamp, secret: &Secp256k1SecretKey) -> Self
+    where
+        T: BcsSignable<'de>,
+    {
+        let timestamped_value = TimestampedValue {
+            value_hash: CryptoHash::new(value),
+            timestamp,
+        };
+        TimestampedSecp256k1Signature {
+            signature: Secp256k1Signature::new(&timestamped_value, secret),
+            timestamp,
+        }
+    }
+
+    /// Checks that the signature is valid for `value` and was created at most `max_age`
+    /// before `now`.
+    ///
+    /// Signatures dated after `now` are rejected, since they would otherwise stay valid for
+    /// longer than `max_age`.
+    pub fn check<'de, T>(
+        &self,
+        value: &T,
+        author: &Secp256k1PublicKey,
+        now: Timestamp,
+        max_age: TimeDelta,
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de>,
+    {
+        if self.timestamp > now {
+            return Err(CryptoError::FutureSignature {
+                timestamp: self.timestamp,
+                now,
+            });
+        }
+        if now.delta_since(self.timestamp) > max_age {
+            return Err(CryptoError::ExpiredSignature {
+                timestamp: self.timestamp,
+                now,
+                max_age,
+            });
+        }
+        let timestamped_value = TimestampedValue {
+            value_hash: CryptoHash::new(value),
+            timestamp: self.timestamp,
+        };
+        self.signature.check(&timestamped_value, author)
+    }
+}
+
+impl From<Secp256k1RecoverableSignature> for Secp256k1Signature {
+    fn from(signature: Secp256k1RecoverableSignature) -> Self {
+        Secp256k1Signature(signature.signature)
+    }
+}
+
+impl Serialize for Secp256k1RecoverableSignature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(self.as_bytes()))
+        } else {
+            let compact = serde_utils::CompactRecoverableSignature(self.as_bytes());
+            serializer.serialize_newtype_struct("Secp256k1RecoverableSignature", &compact)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Secp256k1RecoverableSignature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+            Self::from_slice(&value).map_err(serde::de::Error::custom)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "Secp256k1RecoverableSignature")]
+            struct Signature(serde_utils::CompactRecoverableSignature);
+
+            let value = Signature::deserialize(deserializer)?;
+            Self::from_slice(value.0 .0.as_ref()).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+impl fmt::Display for Secp256k1RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = hex::encode(self.as_bytes());
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Debug for Secp256k1RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..", hex::encode(&self.as_bytes()[0..9]))
+    }
 }
 
 impl Serialize for Secp256k1Signature {
@@ -467,12 +978,18 @@ impl fmt::Debug for Secp256k1Signature {
 
 doc_scalar!(Secp256k1Signature, "A secp256k1 signature value");
 doc_scalar!(Secp256k1PublicKey, "A secp256k1 public key value");
+doc_scalar!(
+    Secp256k1RecoverableSignature,
+    "A secp256k1 signature value from which the public key can be recovered"
+);
 
 mod serde_utils {
     use serde::{Deserialize, Serialize};
     use serde_with::serde_as;
 
-    use super::{SECP256K1_PUBLIC_KEY_SIZE, SECP256K1_SIGNATURE_SIZE};
+    use super::{
+        SECP256K1_PUBLIC_KEY_SIZE, SECP256K1_RECOVERABLE_SIGNATURE_SIZE, SECP256K1_SIGNATURE_SIZE,
+    };
 
     /// Wrapper around compact signature serialization
     /// so that we can implement custom serializer for it that uses fixed length.
@@ -483,6 +1000,14 @@ mod serde_utils {
     #[serde(transparent)]
     pub struct CompactSignature(#[serde_as(as = "[_; 64]")] pub [u8; SECP256K1_SIGNATURE_SIZE]);
 
+    /// Wrapper around compact recoverable signature serialization, using a fixed length.
+    #[serde_as]
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct CompactRecoverableSignature(
+        #[serde_as(as = "[_; 65]")] pub [u8; SECP256K1_RECOVERABLE_SIGNATURE_SIZE],
+    );
+
     #[serde_as]
     #[derive(Serialize, Deserialize)]
     #[serde(transparent)]
@@ -519,6 +1044,277 @@ mod tests {
         assert!(s.check(&foo, &keypair1.public_key).is_err());
     }
 
+    #[test]
+    fn test_key_pair_sign_and_verify() {
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            TestString,
+        };
+
+        let keypair1 = Secp256k1KeyPair::generate();
+        let keypair2 = Secp256k1KeyPair::generate();
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+
+        let signature = keypair1.sign(&ts);
+        assert_eq!(
+            signature,
+            Secp256k1Signature::new(&ts, &keypair1.secret_key)
+        );
+        assert!(keypair1.public_key.verify(&ts, &signature).is_ok());
+        assert!(keypair1.public_key.verify(&tsx, &signature).is_err());
+        assert!(keypair2.public_key.verify(&ts, &signature).is_err());
+    }
+
+    #[test]
+    fn test_verify_batch_detailed() {
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            TestString,
+        };
+
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+        let keypairs = (0..5)
+            .map(|_| Secp256k1KeyPair::generate())
+            .collect::<Vec<_>>();
+        let mut votes = keypairs
+            .iter()
+            .map(|keypair| {
+                (
+                    keypair.public_key,
+                    Secp256k1Signature::new(&ts, &keypair.secret_key),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(Secp256k1Signature::verify_batch_detailed(&ts, &votes).is_ok());
+
+        // One signature for the wrong value, and one from the wrong key.
+        votes[1].1 = Secp256k1Signature::new(&tsx, &keypairs[1].secret_key);
+        votes[3].0 = keypairs[4].public_key;
+        assert_eq!(
+            Secp256k1Signature::verify_batch_detailed(&ts, &votes),
+            Err(vec![1, 3])
+        );
+        assert!(Secp256k1Signature::verify_batch(&ts, &votes).is_err());
+    }
+
+    #[test]
+    fn test_verify_heterogeneous_batch() {
+        use assert_matches::assert_matches;
+
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            CryptoError, TestString,
+        };
+
+        let values = ["hello", "world", "!"].map(|value| TestString(value.into()));
+        let keypairs = (0..3)
+            .map(|_| Secp256k1KeyPair::generate())
+            .collect::<Vec<_>>();
+        let mut signatures = values
+            .iter()
+            .zip(&keypairs)
+            .map(|(value, keypair)| Secp256k1Signature::new(value, &keypair.secret_key))
+            .collect::<Vec<_>>();
+        let public_keys = keypairs
+            .iter()
+            .map(|keypair| keypair.public_key)
+            .collect::<Vec<_>>();
+        let items = values.iter().zip(&public_keys).zip(&signatures);
+        assert!(Secp256k1Signature::verify_heterogeneous_batch(
+            items.map(|((value, public_key), signature)| (value, public_key, signature))
+        )
+        .is_ok());
+
+        // The second signature is valid, but for the third value.
+        signatures[1] = Secp256k1Signature::new(&values[2], &keypairs[1].secret_key);
+        let items = values.iter().zip(&public_keys).zip(&signatures);
+        assert_matches!(
+            Secp256k1Signature::verify_heterogeneous_batch(
+                items.map(|((value, public_key), signature)| (value, public_key, signature))
+            ),
+            Err((1, CryptoError::InvalidSignature { .. }))
+        );
+    }
+
+    #[test]
+    fn test_verify_quorum() {
+        use assert_matches::assert_matches;
+
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            CryptoError, TestString,
+        };
+
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+        let keypairs = (0..4)
+            .map(|_| Secp256k1KeyPair::generate())
+            .collect::<Vec<_>>();
+        let mut votes = keypairs
+            .iter()
+            .map(|keypair| {
+                (
+                    keypair.public_key,
+                    Secp256k1Signature::new(&ts, &keypair.secret_key),
+                )
+            })
+            .collect::<Vec<_>>();
+        // Only the first three signatures are valid.
+        votes[3].1 = Secp256k1Signature::new(&tsx, &keypairs[3].secret_key);
+        assert!(Secp256k1Signature::verify_quorum(&ts, &votes, 3).is_ok());
+        assert!(Secp256k1Signature::verify_quorum(&ts, &votes, 0).is_ok());
+        assert_matches!(
+            Secp256k1Signature::verify_quorum(&ts, &votes, 4),
+            Err(CryptoError::InsufficientValidSignatures {
+                valid: 3,
+                threshold: 4
+            })
+        );
+        assert_matches!(
+            Secp256k1Signature::verify_quorum(&tsx, &votes, 2),
+            Err(CryptoError::InsufficientValidSignatures {
+                valid: 1,
+                threshold: 2
+            })
+        );
+
+        // A single signer repeating its valid vote does not make a quorum.
+        let repeated = vec![votes[0]; 3];
+        assert_matches!(
+            Secp256k1Signature::verify_quorum(&ts, &repeated, 3),
+            Err(CryptoError::DuplicateSigner(signer))
+                if signer == keypairs[0].public_key.to_string()
+        );
+    }
+
+    #[test]
+    fn test_timestamped_signature() {
+        use assert_matches::assert_matches;
+
+        use crate::{
+            crypto::{
+                secp256k1::{Secp256k1KeyPair, TimestampedSecp256k1Signature},
+                CryptoError, TestString,
+            },
+            data_types::{TimeDelta, Timestamp},
+        };
+
+        let keypair = Secp256k1KeyPair::generate();
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+        let max_age = TimeDelta::from_millis(60_000);
+        let signed_at = Timestamp::from(1_000_000_000);
+        let signature = TimestampedSecp256k1Signature::new(&ts, signed_at, &keypair.secret_key);
+        let check = |signature: &TimestampedSecp256k1Signature, now: Timestamp| {
+            signature.check(&ts, &keypair.public_key, now, max_age)
+        };
+
+        assert!(check(&signature, signed_at).is_ok());
+        assert!(check(&signature, signed_at.saturating_add(max_age)).is_ok());
+        assert!(signature
+            .check(&tsx, &keypair.public_key, signed_at, max_age)
+            .is_err());
+
+        let now = signed_at.saturating_add(TimeDelta::from_millis(61_000));
+        assert_matches!(
+            check(&signature, now),
+            Err(CryptoError::ExpiredSignature { timestamp, .. }) if timestamp == signed_at
+        );
+
+        // A signature dated in the future is rejected even though it is not expired.
+        let earlier = Timestamp::from(signed_at.micros() - 1);
+        assert_matches!(
+            check(&signature, earlier),
+            Err(CryptoError::FutureSignature { timestamp, now })
+                if timestamp == signed_at && now == earlier
+        );
+
+        // Moving the timestamp forward to avoid expiry invalidates the signature.
+        let tampered = TimestampedSecp256k1Signature {
+            timestamp: now,
+            ..signature
+        };
+        assert_matches!(
+            check(&tampered, now),
+            Err(CryptoError::InvalidSignature { .. })
+        );
+    }
+
+    #[test]
+    fn test_aggregated_signatures() {
+        use crate::crypto::{
+            secp256k1::{AggregatedSecp256k1, Secp256k1KeyPair, Secp256k1Signature},
+            TestString,
+        };
+
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+        let keypairs = (0..3)
+            .map(|_| Secp256k1KeyPair::generate())
+            .collect::<Vec<_>>();
+        let votes = keypairs
+            .iter()
+            .map(|keypair| {
+                (
+                    keypair.public_key,
+                    Secp256k1Signature::new(&ts, &keypair.secret_key),
+                )
+            })
+            .collect::<Vec<_>>();
+        let aggregated = Secp256k1Signature::aggregate(&votes);
+        assert!(aggregated.verify(&ts).is_ok());
+        assert!(aggregated.verify(&tsx).is_err());
+        assert!(aggregated
+            .signers()
+            .eq(keypairs.iter().map(|keypair| &keypair.public_key)));
+
+        let bytes = bcs::to_bytes(&aggregated).unwrap();
+        assert_eq!(bytes.len(), 1 + 3 * (33 + 64));
+        let aggregated2: AggregatedSecp256k1 = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(aggregated, aggregated2);
+
+        // Dropping a signature leaves the remaining keys paired with the wrong signatures.
+        let signers = votes.iter().map(|(public_key, _)| *public_key);
+        let signatures = votes.iter().skip(1).map(|(_, signature)| *signature);
+        let dropped = signers.zip(signatures).collect::<Vec<_>>();
+        assert!(Secp256k1Signature::aggregate(&dropped).verify(&ts).is_err());
+        // Dropping a signer along with their signature leaves the others valid, but the
+        // dropped signer is no longer covered.
+        let dropped = Secp256k1Signature::aggregate(&votes[1..]);
+        assert!(dropped.verify(&ts).is_ok());
+        assert!(!dropped.signers().any(|key| *key == keypairs[0].public_key));
+    }
+
+    #[test]
+    fn test_prehash_signatures() {
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            CryptoHash, TestString,
+        };
+
+        let keypair1 = Secp256k1KeyPair::generate();
+        let keypair2 = Secp256k1KeyPair::generate();
+
+        let ts = TestString("hello".into());
+        let hash = CryptoHash::new(&ts);
+        let s = Secp256k1Signature::sign_prehash(&hash, &keypair1.secret_key);
+        assert!(s.check_prehash(&hash, &keypair1.public_key).is_ok());
+        assert!(s.check_prehash(&hash, &keypair2.public_key).is_err());
+        let other_hash = CryptoHash::new(&TestString("hellox".into()));
+        assert!(s.check_prehash(&other_hash, &keypair1.public_key).is_err());
+
+        // Signing the hash of a value is the same as signing the value itself.
+        assert!(s.check(&ts, &keypair1.public_key).is_ok());
+        assert_eq!(s, Secp256k1Signature::new(&ts, &keypair1.secret_key));
+
+        // A signature of an arbitrary hash does not verify for an unrelated value.
+        let s = Secp256k1Signature::sign_prehash(&other_hash, &keypair1.secret_key);
+        assert!(s.check_prehash(&other_hash, &keypair1.public_key).is_ok());
+        assert!(s.check(&ts, &keypair1.public_key).is_err());
+    }
+
     #[test]
     fn test_public_key_serialization() {
         use crate::crypto::secp256k1::Secp256k1PublicKey;
@@ -541,6 +1337,54 @@ mod tests {
         assert_eq!(key_out, key_in);
     }
 
+    #[cfg(with_unsafe_secret_serialization)]
+    #[test]
+    fn test_secret_key_binary_serialization() {
+        use crate::crypto::secp256k1::{Secp256k1KeyPair, Secp256k1SecretKey};
+        let key_in = Secp256k1KeyPair::generate().secret_key;
+        let bytes = bcs::to_bytes(&key_in).unwrap();
+        assert_eq!(bytes, key_in.0.to_bytes().to_vec());
+        let key_out: Secp256k1SecretKey = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(key_out, key_in);
+    }
+
+    #[test]
+    fn test_signature_encodings() {
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            TestString,
+        };
+        let keypair = Secp256k1KeyPair::generate();
+        let ts = TestString("hello".into());
+        let sig = Secp256k1Signature::new(&ts, &keypair.secret_key);
+
+        let compact = sig.to_compact();
+        assert_eq!(compact, sig.as_bytes());
+        assert_eq!(bcs::to_bytes(&sig).unwrap(), compact);
+        assert_eq!(Secp256k1Signature::from_compact(&compact).unwrap(), sig);
+
+        let der = sig.to_der();
+        assert_eq!(der[0], 0x30);
+        assert!(der.len() <= 72);
+        let decoded = Secp256k1Signature::from_der(&der).unwrap();
+        assert_eq!(decoded, sig);
+        assert!(decoded.check(&ts, &keypair.public_key).is_ok());
+        assert!(Secp256k1Signature::from_der(&compact).is_err());
+    }
+
+    #[test]
+    fn test_key_pair_from_secret_bytes() {
+        use crate::crypto::secp256k1::Secp256k1KeyPair;
+        let keypair = Secp256k1KeyPair::generate();
+        let bytes: [u8; 32] = keypair.secret_key.0.to_bytes().into();
+        let restored = Secp256k1KeyPair::from_secret_bytes(&bytes).unwrap();
+        assert_eq!(restored.public_key, keypair.public_key);
+        assert_eq!(restored.secret_key, keypair.secret_key);
+
+        // Zero is not a valid secret key.
+        assert!(Secp256k1KeyPair::from_secret_bytes(&[0; 32]).is_err());
+    }
+
     #[test]
     fn test_signature_serialization() {
         use crate::crypto::{
@@ -558,6 +1402,122 @@ mod tests {
         assert_eq!(sig, sig2);
     }
 
+    #[test]
+    fn test_public_key_ct_eq() {
+        use crate::crypto::secp256k1::Secp256k1PublicKey;
+        let keys = [0, 1, 2].map(Secp256k1PublicKey::test_key);
+        for key1 in &keys {
+            for key2 in &keys {
+                assert_eq!(key1.ct_eq(key2), key1 == key2);
+            }
+            let copy = Secp256k1PublicKey::from_bytes(&key1.as_bytes()).unwrap();
+            assert!(key1.ct_eq(&copy));
+        }
+    }
+
+    #[test]
+    fn test_uncompressed_public_key() {
+        use assert_matches::assert_matches;
+
+        use crate::crypto::{secp256k1::Secp256k1PublicKey, CryptoError};
+        let key = Secp256k1PublicKey::test_key(0);
+        let uncompressed = key.to_uncompressed();
+        assert_eq!(uncompressed[0], 0x04);
+        assert_eq!(uncompressed[1..33], key.as_bytes()[1..]);
+        assert_eq!(
+            Secp256k1PublicKey::from_uncompressed(&uncompressed).unwrap(),
+            key
+        );
+        assert_eq!(
+            Secp256k1PublicKey::from_uncompressed(&uncompressed).unwrap(),
+            Secp256k1PublicKey::from_bytes(&key.as_bytes()).unwrap()
+        );
+
+        let mut invalid = uncompressed;
+        for tag in [0x02, 0x03, 0x07] {
+            invalid[0] = tag;
+            assert_matches!(
+                Secp256k1PublicKey::from_uncompressed(&invalid),
+                Err(CryptoError::Secp256k1InvalidPoint(encoded)) if encoded == hex::encode(invalid)
+            );
+        }
+        invalid = uncompressed;
+        invalid[64] ^= 1;
+        assert_matches!(
+            Secp256k1PublicKey::from_uncompressed(&invalid),
+            Err(CryptoError::Secp256k1InvalidPoint(_))
+        );
+    }
+
+    #[test]
+    fn test_public_key_from_parts() {
+        use assert_matches::assert_matches;
+
+        use crate::crypto::{secp256k1::Secp256k1PublicKey, CryptoError};
+
+        let key = Secp256k1PublicKey::test_key(0);
+        let parts = <(u64, u64, u64, u64, u8)>::from(key);
+        assert_eq!(Secp256k1PublicKey::try_from(parts).unwrap(), key);
+
+        // There is no point on the curve with x = 5.
+        let off_curve = (0x02 << 56, 0, 0, 0, 5);
+        assert_matches!(
+            Secp256k1PublicKey::try_from(off_curve),
+            Err(CryptoError::Secp256k1InvalidPoint(_))
+        );
+    }
+
+    #[test]
+    fn test_public_key_parse_errors() {
+        use std::str::FromStr as _;
+
+        use assert_matches::assert_matches;
+
+        use crate::crypto::{secp256k1::Secp256k1PublicKey, CryptoError};
+
+        assert_matches!(
+            Secp256k1PublicKey::from_str("not hex"),
+            Err(CryptoError::NonHexDigits(_))
+        );
+        let key = Secp256k1PublicKey::test_key(0).to_string();
+        assert_matches!(
+            Secp256k1PublicKey::from_str(&key[..40]),
+            Err(CryptoError::IncorrectPublicKeySize { len: 20, .. })
+        );
+        assert_matches!(
+            Secp256k1PublicKey::from_str(""),
+            Err(CryptoError::IncorrectPublicKeySize { len: 0, .. })
+        );
+        // There is no point on the curve with x = 5.
+        let off_curve = format!("02{:064x}", 5);
+        assert_matches!(
+            Secp256k1PublicKey::from_str(&off_curve),
+            Err(CryptoError::Secp256k1InvalidPoint(_))
+        );
+        let bad_tag = format!("07{}", &key[2..]);
+        assert_matches!(
+            Secp256k1PublicKey::from_str(&bad_tag),
+            Err(CryptoError::Secp256k1InvalidPoint(_))
+        );
+        assert!(Secp256k1PublicKey::from_str(&key).is_ok());
+    }
+
+    #[test]
+    fn test_eth_address() {
+        use k256::ecdsa::SigningKey;
+
+        use crate::crypto::secp256k1::Secp256k1SecretKey;
+
+        // The well-known address of the secret key `1`, whose public key is the generator.
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        let secret_key = Secp256k1SecretKey(SigningKey::from_slice(&bytes).unwrap());
+        assert_eq!(
+            hex::encode(secret_key.public().eth_address()),
+            "7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+    }
+
     #[test]
     fn public_key_from_str() {
         use std::str::FromStr;
@@ -594,4 +1554,100 @@ mod tests {
         let sig2: Secp256k1Signature = serde_json::from_str(&s).unwrap();
         assert_eq!(sig, sig2);
     }
+
+    #[test]
+    fn test_recoverable_signatures() {
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1RecoverableSignature, Secp256k1Signature},
+            TestString,
+        };
+
+        let keypair1 = Secp256k1KeyPair::generate();
+        let keypair2 = Secp256k1KeyPair::generate();
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+
+        let sig = Secp256k1RecoverableSignature::new_recoverable(&ts, &keypair1.secret_key);
+        assert_eq!(sig.recover(&ts).unwrap(), keypair1.public_key);
+        assert_ne!(sig.recover(&tsx).ok(), Some(keypair1.public_key));
+        assert!(sig.check(&ts, &keypair1.public_key).is_ok());
+        assert!(sig.check(&ts, &keypair2.public_key).is_err());
+        assert!(Secp256k1Signature::from(sig)
+            .check(&ts, &keypair1.public_key)
+            .is_ok());
+
+        let s = serde_json::to_string(&sig).unwrap();
+        let sig2: Secp256k1RecoverableSignature = serde_json::from_str(&s).unwrap();
+        assert_eq!(sig, sig2);
+
+        let s = bcs::to_bytes(&sig).unwrap();
+        assert_eq!(s.len(), 65);
+        let sig2: Secp256k1RecoverableSignature = bcs::from_bytes(&s).unwrap();
+        assert_eq!(sig, sig2);
+        assert_eq!(sig2.recover(&ts).unwrap(), keypair1.public_key);
+
+        assert!(Secp256k1RecoverableSignature::from_slice(&s[..64]).is_err());
+    }
+
+    #[test]
+    fn test_high_s_signatures_are_rejected() {
+        use k256::ecdsa::Signature;
+
+        use crate::crypto::{
+            secp256k1::{Secp256k1KeyPair, Secp256k1Signature},
+            CryptoError, TestString,
+        };
+
+        let keypair = Secp256k1KeyPair::generate();
+        let ts = TestString("hello".into());
+        let sig = Secp256k1Signature::new(&ts, &keypair.secret_key);
+        assert!(sig.0.normalize_s().is_none());
+        assert!(sig.check(&ts, &keypair.public_key).is_ok());
+
+        let (r, s) = sig.0.split_scalars();
+        let high_s = Secp256k1Signature(Signature::from_scalars(r, -*s).unwrap());
+        assert_ne!(high_s, sig);
+        assert_eq!(high_s.0.normalize_s(), Some(sig.0));
+        assert!(matches!(
+            high_s.check(&ts, &keypair.public_key),
+            Err(CryptoError::InvalidSignature { .. })
+        ));
+        let votes = [(keypair.public_key, sig), (keypair.public_key, high_s)];
+        assert!(Secp256k1Signature::verify_batch(&ts, &votes[..1]).is_ok());
+        assert!(Secp256k1Signature::verify_batch(&ts, &votes).is_err());
+    }
+
+    /// Drops a secret key in place and checks that its scalar no longer appears in the
+    /// memory it occupied. The scalar is stored as little-endian limbs, so its bytes appear in
+    /// reverse order.
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn test_secret_key_zeroize_on_drop() {
+        use std::mem::{size_of, MaybeUninit};
+
+        use crate::crypto::secp256k1::{Secp256k1KeyPair, Secp256k1SecretKey};
+
+        fn contains(memory: &MaybeUninit<Secp256k1SecretKey>, secret: &[u8]) -> bool {
+            // SAFETY: The storage is valid for reads of its own size, and was fully written
+            // when the key was created.
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    memory.as_ptr().cast::<u8>(),
+                    size_of::<Secp256k1SecretKey>(),
+                )
+            };
+            bytes.windows(secret.len()).any(|window| window == secret)
+        }
+
+        let mut memory = MaybeUninit::new(Secp256k1KeyPair::generate().secret_key);
+        // SAFETY: The key was just written.
+        let mut secret = unsafe { memory.assume_init_ref() }.0.to_bytes().to_vec();
+        secret.reverse();
+        assert!(contains(&memory, &secret));
+
+        // SAFETY: The key is initialized, and is not used again after being dropped.
+        unsafe { memory.assume_init_drop() };
+        // The scalar is overwritten with one rather than zero, to keep it a valid secret key.
+        assert!(!contains(&memory, &secret));
+    }
 }
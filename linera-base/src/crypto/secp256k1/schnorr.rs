@@ -0,0 +1,225 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Defines BIP-340 Schnorr signature primitives over secp256k1.
+
+use std::fmt;
+
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    serde_utils, Secp256k1PublicKey, Secp256k1SecretKey, SECP256K1_SCHEME_LABEL,
+    SECP256K1_SIGNATURE_SIZE,
+};
+use crate::{
+    crypto::{BcsSignable, CryptoError, CryptoHash, HasTypeName},
+    doc_scalar,
+};
+
+/// A BIP-340 Schnorr signature over secp256k1.
+///
+/// Signatures are created with the same keys as [`super::Secp256k1Signature`]s. BIP-340 only
+/// uses the x-coordinate of the public key.
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct Secp256k1SchnorrSignature(pub Signature);
+
+impl Secp256k1SchnorrSignature {
+    /// Computes a Schnorr signature for `value` using the given `secret`.
+    /// It first serializes the `T` type and then creates the `CryptoHash` from the serialized bytes.
+    pub fn new<'de, T>(value: &T, secret: &Secp256k1SecretKey) -> Self
+    where
+        T: BcsSignable<'de>,
+    {
+        use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        let signing_key = SigningKey::from(*secret.0.as_nonzero_scalar());
+        let signature = signing_key
+            .sign_prehash(&prehash)
+            .expect("Failed to sign prehashed data"); // NOTE: This is a critical error we don't control.
+        Secp256k1SchnorrSignature(signature)
+    }
+
+    /// Checks a signature.
+    pub fn check<'de, T>(&self, value: &T, author: &Secp256k1PublicKey) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        self.verify_inner::<T>(prehash, author)
+    }
+
+    /// Verifies a batch of signatures.
+    ///
+    /// Returns an error on first failed signature.
+    pub fn verify_batch<'a, 'de, T, I>(value: &'a T, votes: I) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+        I: IntoIterator<Item = &'a (Secp256k1PublicKey, Secp256k1SchnorrSignature)>,
+    {
+        let prehash = CryptoHash::new(value).as_bytes().0;
+        for (author, signature) in votes {
+            signature.verify_inner::<T>(prehash, author)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the byte representation of the signature.
+    pub fn as_bytes(&self) -> [u8; SECP256K1_SIGNATURE_SIZE] {
+        self.0.to_bytes()
+    }
+
+    fn verify_inner<'de, T>(
+        &self,
+        prehash: [u8; 32],
+        author: &Secp256k1PublicKey,
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+        let x_only = author.0.to_encoded_point(true);
+        // UNWRAP: A compressed point always contains the x-coordinate.
+        VerifyingKey::from_bytes(x_only.x().unwrap())
+            .and_then(|key| key.verify_prehash(&prehash, &self.0))
+            .map_err(|error| CryptoError::InvalidSignature {
+                error: error.to_string(),
+                type_name: T::type_name().to_string(),
+            })
+    }
+
+    /// Creates a signature from the bytes.
+    /// Expects the signature to be serialized in raw-bytes form.
+    pub fn from_slice<A: AsRef<[u8]>>(bytes: A) -> Result<Self, CryptoError> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != SECP256K1_SIGNATURE_SIZE {
+            return Err(CryptoError::IncorrectSignatureBytes {
+                scheme: SECP256K1_SCHEME_LABEL,
+                len: bytes.len(),
+                expected: SECP256K1_SIGNATURE_SIZE,
+            });
+        }
+        let signature = Signature::try_from(bytes).map_err(CryptoError::Secp256k1Error)?;
+        Ok(Secp256k1SchnorrSignature(signature))
+    }
+}
+
+impl Serialize for Secp256k1SchnorrSignature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(self.as_bytes()))
+        } else {
+            let compact = serde_utils::CompactSignature(self.as_bytes());
+            serializer.serialize_newtype_struct("Secp256k1SchnorrSignature", &compact)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Secp256k1SchnorrSignature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+            Self::from_slice(&value).map_err(serde::de::Error::custom)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "Secp256k1SchnorrSignature")]
+            struct Signature(serde_utils::CompactSignature);
+
+            let value = Signature::deserialize(deserializer)?;
+            Self::from_slice(value.0 .0.as_ref()).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+impl fmt::Display for Secp256k1SchnorrSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = hex::encode(self.as_bytes());
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Debug for Secp256k1SchnorrSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..", hex::encode(&self.as_bytes()[0..9]))
+    }
+}
+
+doc_scalar!(
+    Secp256k1SchnorrSignature,
+    "A BIP-340 Schnorr signature over secp256k1"
+);
+
+#[cfg(with_testing)]
+mod tests {
+    #[test]
+    fn test_signatures() {
+        use serde::{Deserialize, Serialize};
+
+        use crate::crypto::{
+            secp256k1::{schnorr::Secp256k1SchnorrSignature, Secp256k1KeyPair, Secp256k1Signature},
+            BcsSignable, TestString,
+        };
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Foo(String);
+
+        impl BcsSignable<'_> for Foo {}
+
+        let keypair1 = Secp256k1KeyPair::generate();
+        let keypair2 = Secp256k1KeyPair::generate();
+
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+        let foo = Foo("hello".into());
+
+        let s = Secp256k1SchnorrSignature::new(&ts, &keypair1.secret_key);
+        assert!(s.check(&ts, &keypair1.public_key).is_ok());
+        assert!(s.check(&ts, &keypair2.public_key).is_err());
+        assert!(s.check(&tsx, &keypair1.public_key).is_err());
+        assert!(s.check(&foo, &keypair1.public_key).is_err());
+
+        let votes = [
+            (keypair1.public_key, s),
+            (
+                keypair2.public_key,
+                Secp256k1SchnorrSignature::new(&ts, &keypair2.secret_key),
+            ),
+        ];
+        assert!(Secp256k1SchnorrSignature::verify_batch(&ts, &votes).is_ok());
+        assert!(Secp256k1SchnorrSignature::verify_batch(&tsx, &votes).is_err());
+
+        // An ECDSA signature is not a valid Schnorr signature, and vice versa.
+        let ecdsa = Secp256k1Signature::new(&ts, &keypair1.secret_key);
+        let confused = Secp256k1SchnorrSignature::from_slice(ecdsa.as_bytes());
+        assert!(confused.map_or(true, |sig| sig.check(&ts, &keypair1.public_key).is_err()));
+        let confused = Secp256k1Signature::from_slice(s.as_bytes()).unwrap();
+        assert!(confused.check(&ts, &keypair1.public_key).is_err());
+    }
+
+    #[test]
+    fn test_signature_serialization() {
+        use crate::crypto::{
+            secp256k1::{schnorr::Secp256k1SchnorrSignature, Secp256k1KeyPair},
+            TestString,
+        };
+        let keypair = Secp256k1KeyPair::generate();
+        let sig = Secp256k1SchnorrSignature::new(&TestString("hello".into()), &keypair.secret_key);
+        let s = serde_json::to_string(&sig).unwrap();
+        let sig2: Secp256k1SchnorrSignature = serde_json::from_str(&s).unwrap();
+        assert_eq!(sig, sig2);
+
+        let s = bcs::to_bytes(&sig).unwrap();
+        assert_eq!(s.len(), 64);
+        let sig2: Secp256k1SchnorrSignature = bcs::from_bytes(&s).unwrap();
+        assert_eq!(sig, sig2);
+    }
+}
@@ -901,8 +901,39 @@ pub enum OracleResponse {
     Event(EventId, Vec<u8>),
 }
 
+impl OracleResponse {
+    /// Returns the kind of this oracle response.
+    pub fn kind(&self) -> OracleResponseKind {
+        match self {
+            OracleResponse::Service(_) => OracleResponseKind::Service,
+            OracleResponse::Http(_) => OracleResponseKind::Http,
+            OracleResponse::Blob(_) => OracleResponseKind::Blob,
+            OracleResponse::Assert => OracleResponseKind::Assert,
+            OracleResponse::Round(_) => OracleResponseKind::Round,
+            OracleResponse::Event(_, _) => OracleResponseKind::Event,
+        }
+    }
+}
+
 impl BcsHashable<'_> for OracleResponse {}
 
+/// The kind of an [`OracleResponse`], without its payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OracleResponseKind {
+    /// The response from a service query.
+    Service,
+    /// The response from an HTTP request.
+    Http,
+    /// A successful read or write of a blob.
+    Blob,
+    /// An assertion oracle that passed.
+    Assert,
+    /// The block's validation round.
+    Round,
+    /// An event was read.
+    Event,
+}
+
 /// Description of a user application.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Hash, Serialize)]
 pub struct ApplicationDescription {
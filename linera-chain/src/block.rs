@@ -4,29 +4,49 @@
 
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, BTreeSet},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashSet},
     fmt::Debug,
+    sync::LazyLock,
 };
 
 use async_graphql::SimpleObject;
 use linera_base::{
-    crypto::{BcsHashable, CryptoHash},
-    data_types::{Blob, BlockHeight, Epoch, Event, OracleResponse, Timestamp},
+    bcs,
+    crypto::{
+        AccountPublicKey, AccountSecretKey, AccountSignature, BcsHashable, BcsSignable,
+        CryptoError, CryptoHash, CryptoHashVec,
+    },
+    data_types::{
+        ApplicationDescription, Blob, BlockHeight, Epoch, Event, OracleResponse,
+        OracleResponseKind, Timestamp,
+    },
+    ensure,
     hashed::Hashed,
-    identifiers::{AccountOwner, BlobId, ChainId, MessageId},
+    identifiers::{
+        AccountOwner, ApplicationId, BlobId, BlobType, ChainId, ChannelName, Destination, EventId,
+        GenericApplicationId, MessageId,
+    },
+};
+use linera_execution::{
+    system::OpenChainConfig, BlobState, MessageKind, Operation, OutgoingMessage, SystemOperation,
 };
-use linera_execution::{system::OpenChainConfig, BlobState, Operation, OutgoingMessage};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
     data_types::{
         BlockExecutionOutcome, IncomingBundle, Medium, MessageAction, MessageBundle,
-        OperationResult, OutgoingMessageExt, PostedMessage, ProposedBlock,
+        OperationResult, Origin, OutgoingMessageExt, PostedMessage, ProposedBlock, Transaction,
     },
-    types::CertificateValue,
+    types::{CertificateKind, CertificateValue},
+    ChainError,
 };
 
+#[cfg(test)]
+#[path = "unit_tests/block_tests.rs"]
+mod block_tests;
+
 /// Wrapper around a `Block` that has been validated.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -73,6 +93,16 @@ impl ValidatedBlock {
     }
 }
 
+impl From<ValidatedBlock> for ConfirmedBlock {
+    /// Confirms a validated block, reusing its hash instead of hashing the block again.
+    ///
+    /// Both wrappers have the same hash as their inner block; votes and certificates for
+    /// them are told apart by their [`CertificateKind`].
+    fn from(validated: ValidatedBlock) -> Self {
+        ConfirmedBlock::from_hashed(validated.0)
+    }
+}
+
 /// Wrapper around a `Block` that has been confirmed.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -116,6 +146,20 @@ impl ConfirmedBlock {
         self.0.inner()
     }
 
+    /// Recomputes the hash of the block and checks that it matches the stored one.
+    ///
+    /// Deserialization always recomputes the hash, but a `ConfirmedBlock` created from a
+    /// [`Hashed::unchecked_new`] value, e.g. from untrusted storage, may be inconsistent.
+    pub fn reverify_hash(&self) -> Result<(), ChainError> {
+        let actual = CryptoHash::new(self.block());
+        let expected = self.0.hash();
+        ensure!(
+            actual == expected,
+            ChainError::CertificateValueHashMismatch { expected, actual }
+        );
+        Ok(())
+    }
+
     /// Consumes this `ConfirmedBlock`, returning the `Block` it contains.
     pub fn into_block(self) -> Block {
         self.0.into_inner()
@@ -138,6 +182,12 @@ impl ConfirmedBlock {
         self.block().matches_proposed_block(block)
     }
 
+    /// Returns whether this is the same block as the validated one. Only the hashes are
+    /// compared.
+    pub fn matches_validated(&self, validated: &ValidatedBlock) -> bool {
+        self.0.hash() == validated.inner().hash()
+    }
+
     /// Returns a blob state that applies to all blobs used by this block.
     pub fn to_blob_state(&self) -> BlobState {
         BlobState {
@@ -149,6 +199,26 @@ impl ConfirmedBlock {
     }
 }
 
+/// Checks that each block has the given hash, hashing the blocks in parallel on the rayon
+/// thread pool.
+///
+/// Returns an error for the first mismatch in the list.
+#[cfg(with_rayon)]
+pub fn verify_blocks_parallel(blocks: &[(CryptoHash, Block)]) -> Result<(), ChainError> {
+    use rayon::prelude::*;
+
+    let first_mismatch = blocks.par_iter().find_map_first(|(expected, block)| {
+        let actual = CryptoHash::new(block);
+        (actual != *expected).then_some((*expected, actual))
+    });
+    match first_mismatch {
+        None => Ok(()),
+        Some((expected, actual)) => {
+            Err(ChainError::CertificateValueHashMismatch { expected, actual })
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Timeout(Hashed<TimeoutInner>);
@@ -190,25 +260,232 @@ impl Timeout {
     pub fn inner(&self) -> &Hashed<TimeoutInner> {
         &self.0
     }
+
+    /// Returns whether this timeout is for the same chain as `other`, but for a later epoch or
+    /// a later height within the same epoch.
+    pub fn supersedes(&self, other: &Timeout) -> bool {
+        self.chain_id() == other.chain_id() && self > other
+    }
+}
+
+impl Ord for Timeout {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |timeout: &Timeout| (timeout.chain_id(), timeout.epoch(), timeout.height());
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Timeout {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl BcsHashable<'_> for Timeout {}
 impl BcsHashable<'_> for TimeoutInner {}
 
+/// A block together with the signature of its author, to be propagated independently of a
+/// proposal or certificate.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SignedBlock {
+    pub block: Hashed<Block>,
+    pub author: AccountOwner,
+    pub public_key: AccountPublicKey,
+    pub signature: AccountSignature,
+}
+
+/// The value signed by the author of a [`SignedBlock`].
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedBlockContent {
+    block_hash: CryptoHash,
+}
+
+impl BcsSignable<'_> for SignedBlockContent {}
+
+impl SignedBlock {
+    /// Signs the hash of the block with the given secret key.
+    pub fn new(block: Hashed<Block>, secret: &AccountSecretKey) -> Self {
+        let public_key = secret.public();
+        let signature = secret.sign(&SignedBlockContent {
+            block_hash: block.hash(),
+        });
+        SignedBlock {
+            block,
+            author: public_key.into(),
+            public_key,
+            signature,
+        }
+    }
+
+    /// Checks that the public key belongs to the author and that the signature is valid.
+    pub fn verify(&self) -> Result<(), CryptoError> {
+        check_author(self.author, self.public_key, "SignedBlock")?;
+        let content = SignedBlockContent {
+            block_hash: self.block.hash(),
+        };
+        self.signature.verify(&content, self.public_key)
+    }
+}
+
+/// A timeout together with the signature of the owner who requested it, to be handled
+/// like a [`SignedBlock`].
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SignedTimeout {
+    pub timeout: Timeout,
+    pub author: AccountOwner,
+    pub public_key: AccountPublicKey,
+    pub signature: AccountSignature,
+}
+
+/// The value signed by the author of a [`SignedTimeout`].
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedTimeoutContent {
+    timeout_hash: CryptoHash,
+}
+
+impl BcsSignable<'_> for SignedTimeoutContent {}
+
+impl SignedTimeout {
+    /// Signs the hash of the timeout with the given secret key.
+    pub fn new(timeout: Timeout, secret: &AccountSecretKey) -> Self {
+        let public_key = secret.public();
+        let signature = secret.sign(&SignedTimeoutContent {
+            timeout_hash: timeout.inner().hash(),
+        });
+        SignedTimeout {
+            timeout,
+            author: public_key.into(),
+            public_key,
+            signature,
+        }
+    }
+
+    /// Checks that the public key belongs to the author and that the signature is valid.
+    pub fn verify(&self) -> Result<(), CryptoError> {
+        check_author(self.author, self.public_key, "SignedTimeout")?;
+        let content = SignedTimeoutContent {
+            timeout_hash: self.timeout.inner().hash(),
+        };
+        self.signature.verify(&content, self.public_key)
+    }
+}
+
+/// Returns the sum of the lengths, or `None` if it doesn't fit into a `u32`.
+fn checked_u32_sum(lengths: impl IntoIterator<Item = usize>) -> Option<u32> {
+    lengths
+        .into_iter()
+        .try_fold(0u32, |sum, len| sum.checked_add(u32::try_from(len).ok()?))
+}
+
+/// Checks that the `public_key` belongs to the `author` of a signed value.
+fn check_author(
+    author: AccountOwner,
+    public_key: AccountPublicKey,
+    type_name: &str,
+) -> Result<(), CryptoError> {
+    ensure!(
+        AccountOwner::from(public_key) == author,
+        CryptoError::InvalidSignature {
+            error: format!("public key does not belong to the author {author}"),
+            type_name: type_name.to_string(),
+        }
+    );
+    Ok(())
+}
+
 /// Failure to convert a `Certificate` into one of the expected certificate types.
 #[derive(Clone, Copy, Debug, Error)]
 pub enum ConversionError {
     /// Failure to convert to [`ConfirmedBlock`] certificate.
-    #[error("Expected a `ConfirmedBlockCertificate` value")]
-    ConfirmedBlock,
+    #[error("Expected a `ConfirmedBlockCertificate` value, found a {found:?} certificate")]
+    ConfirmedBlock { found: CertificateKind },
 
     /// Failure to convert to [`ValidatedBlock`] certificate.
-    #[error("Expected a `ValidatedBlockCertificate` value")]
-    ValidatedBlock,
+    #[error("Expected a `ValidatedBlockCertificate` value, found a {found:?} certificate")]
+    ValidatedBlock { found: CertificateKind },
 
     /// Failure to convert to [`Timeout`] certificate.
-    #[error("Expected a `TimeoutCertificate` value")]
-    Timeout,
+    #[error("Expected a `TimeoutCertificate` value, found a {found:?} certificate")]
+    Timeout { found: CertificateKind },
+}
+
+impl ConversionError {
+    /// Returns the name of the expected certificate type.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            ConversionError::ConfirmedBlock { .. } => "confirmed",
+            ConversionError::ValidatedBlock { .. } => "validated",
+            ConversionError::Timeout { .. } => "timeout",
+        }
+    }
+
+    /// Returns the kind of the certificate that was found instead.
+    pub fn found(&self) -> CertificateKind {
+        match self {
+            ConversionError::ConfirmedBlock { found }
+            | ConversionError::ValidatedBlock { found }
+            | ConversionError::Timeout { found } => *found,
+        }
+    }
+}
+
+/// Failure to look up an outgoing message in a block by its ID.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum MessageLookupError {
+    /// The message ID refers to a different chain.
+    #[error("Message ID is for chain {requested}, but the block is from chain {block}")]
+    WrongChain { requested: ChainId, block: ChainId },
+
+    /// The message ID refers to a different block height.
+    #[error("Message ID is for height {requested}, but the block has height {block}")]
+    WrongHeight {
+        requested: BlockHeight,
+        block: BlockHeight,
+    },
+
+    /// The block has fewer outgoing messages than the index.
+    #[error("Message index {requested} is out of range: the block has {available} messages")]
+    IndexOutOfRange { requested: u32, available: usize },
+}
+
+/// Failure to compute the ID of a message created by an operation.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum MessageIdError {
+    /// The block has fewer operations than the index.
+    #[error("Operation index {requested} is out of range: the block has {available} operations")]
+    OperationOutOfRange { requested: usize, available: usize },
+
+    /// The operation created fewer messages than the index.
+    #[error(
+        "Message index {requested} is out of range: the operation created {available} messages"
+    )]
+    MessageOutOfRange { requested: u32, available: usize },
+
+    /// The index of the message in the block does not fit into a `u32`.
+    #[error("The index of the message in the block overflows")]
+    IndexOverflow,
+}
+
+/// The prefix of [`Block::to_canonical_bytes`], identifying the bytes as a block.
+const BLOCK_BYTES_MAGIC: [u8; 4] = *b"LNBK";
+
+/// The current version of the format of [`Block::to_canonical_bytes`].
+const BLOCK_BYTES_VERSION: u8 = 1;
+
+/// Failure to decode a block with [`Block::from_canonical_bytes`].
+#[derive(Debug, Error)]
+pub enum BlockDecodingError {
+    /// The bytes don't start with the block prefix.
+    #[error("The bytes are not an encoded block")]
+    MissingMagic,
+
+    /// The bytes were written in a format version this code doesn't know.
+    #[error("Unknown block format version {0}")]
+    UnknownVersion(u8),
+
+    /// The block itself could not be deserialized.
+    #[error("Failed to deserialize the block: {0}")]
+    Bcs(#[from] bcs::Error),
 }
 
 /// Block defines the atomic unit of growth of the Linera chain.
@@ -218,6 +495,7 @@ pub enum ConversionError {
 /// Resulting messages produced by the operations are also included in the block body,
 /// together with oracle responses and events.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, SimpleObject)]
+#[graphql(complex)]
 pub struct Block {
     /// Header of the block containing metadata of the block.
     pub header: BlockHeader,
@@ -225,6 +503,34 @@ pub struct Block {
     pub body: BlockBody,
 }
 
+#[async_graphql::ComplexObject]
+impl Block {
+    /// The outgoing messages of the block, in order, together with their IDs.
+    async fn flattened_messages(&self) -> Vec<OutgoingMessageWithId> {
+        self.outgoing_messages_with_ids()
+            .map(|(message_id, message)| OutgoingMessageWithId {
+                message_id,
+                message: message.clone(),
+            })
+            .collect()
+    }
+
+    /// The IDs of the blobs required by the block, in ascending order.
+    #[graphql(derived(name = "required_blob_ids"))]
+    async fn _required_blob_ids(&self) -> Vec<BlobId> {
+        self.required_blob_ids().into_iter().collect()
+    }
+}
+
+/// An outgoing message of a block, together with its ID.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct OutgoingMessageWithId {
+    /// The ID of the message.
+    pub message_id: MessageId,
+    /// The message itself.
+    pub message: OutgoingMessage,
+}
+
 impl Serialize for Block {
     fn serialize<S: serde::ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut state = serializer.serialize_struct("Block", 2)?;
@@ -334,6 +640,167 @@ pub struct BlockHeader {
     pub operation_results_hash: CryptoHash,
 }
 
+impl BlockHeader {
+    /// Returns whether this is the header of `block`, i.e. whether it equals the block's
+    /// header and all its hashes match the block's body.
+    pub fn verify_against(&self, block: &Block) -> bool {
+        *self == block.header && self.validate_body_hashes(&block.body, true).is_ok()
+    }
+
+    /// Returns whether the `proof` shows that `operation` is included in the block with this
+    /// header.
+    pub fn verify_operation_proof(&self, operation: &Operation, proof: &MerkleProof) -> bool {
+        proof.levels.len() == 1 && proof.verify(CryptoHash::new(operation), self.operations_hash)
+    }
+
+    /// Returns whether the `proof` shows that `message` is included in the block with this
+    /// header.
+    pub fn verify_message_proof(&self, message: &OutgoingMessage, proof: &MerkleProof) -> bool {
+        proof.levels.len() == 2 && proof.verify(CryptoHash::new(message), self.messages_hash)
+    }
+
+    /// Checks that the hashes in this header match the contents of the `body`. If
+    /// `check_oracle_responses` is false, the oracle responses are not checked.
+    fn validate_body_hashes(
+        &self,
+        body: &BlockBody,
+        check_oracle_responses: bool,
+    ) -> Result<(), ChainError> {
+        let hashes = body.hashes();
+        for (field, expected, actual) in [
+            ("bundles_hash", self.bundles_hash, hashes.bundles_hash),
+            (
+                "operations_hash",
+                self.operations_hash,
+                hashes.operations_hash,
+            ),
+            ("messages_hash", self.messages_hash, hashes.messages_hash),
+            (
+                "previous_message_blocks_hash",
+                self.previous_message_blocks_hash,
+                hashes.previous_message_blocks_hash,
+            ),
+            (
+                "oracle_responses_hash",
+                self.oracle_responses_hash,
+                hashes.oracle_responses_hash,
+            ),
+            ("events_hash", self.events_hash, hashes.events_hash),
+            ("blobs_hash", self.blobs_hash, hashes.blobs_hash),
+            (
+                "operation_results_hash",
+                self.operation_results_hash,
+                hashes.operation_results_hash,
+            ),
+        ] {
+            if field == "oracle_responses_hash" && !check_oracle_responses {
+                continue;
+            }
+            ensure!(
+                expected == actual,
+                ChainError::BlockHeaderHashMismatch { field }
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A block whose oracle responses were removed, as returned by
+/// [`Block::redact_oracle_responses`]. The header is unchanged, so its
+/// `oracle_responses_hash` still commits to the removed responses.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RedactedBlock {
+    /// The header of the original block.
+    pub header: BlockHeader,
+    /// The body of the original block, without oracle responses.
+    pub body: BlockBody,
+}
+
+impl RedactedBlock {
+    /// Checks that the hashes in the header match the contents of the body, except for the
+    /// redacted oracle responses.
+    pub fn verify_header(&self) -> Result<(), ChainError> {
+        self.header.validate_body_hashes(&self.body, false)
+    }
+
+    /// Restores the original block from the redacted block and its oracle responses. Fails
+    /// if any hash in the header does not match.
+    pub fn with_oracle_responses(
+        self,
+        oracle_responses: Vec<Vec<OracleResponse>>,
+    ) -> Result<Block, ChainError> {
+        let block = Block {
+            header: self.header,
+            body: BlockBody {
+                oracle_responses,
+                ..self.body
+            },
+        };
+        block.validate_hashes()?;
+        Ok(block)
+    }
+}
+
+/// A proof that an element is included in a block, relative to one of the hashes in the
+/// [`BlockHeader`].
+///
+/// The header hashes are the roots of Merkle trees where every node is the hash of the
+/// [`CryptoHashVec`] of its children: `operations_hash` has the operations as direct
+/// children, while e.g. `messages_hash` has one subtree per transaction. A proof therefore
+/// contains, for each level from the leaf up to the root, the position of the node and the
+/// hashes of its siblings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    levels: Vec<MerkleProofLevel>,
+}
+
+/// One level of a [`MerkleProof`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct MerkleProofLevel {
+    /// The position of the node among its siblings.
+    index: u32,
+    /// The hashes of all the other children of the parent node, in order.
+    siblings: Vec<CryptoHash>,
+}
+
+impl MerkleProof {
+    /// Creates a proof from the position and the hashes of all the nodes at each level,
+    /// starting with the leaves. Returns `None` if any position is out of range.
+    fn from_levels(levels: Vec<(usize, Vec<CryptoHash>)>) -> Option<Self> {
+        let levels = levels
+            .into_iter()
+            .map(|(index, mut siblings)| {
+                if index >= siblings.len() {
+                    return None;
+                }
+                siblings.remove(index);
+                Some(MerkleProofLevel {
+                    index: u32::try_from(index).ok()?,
+                    siblings,
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(Self { levels })
+    }
+
+    /// Returns whether this proof connects the `leaf` hash to the `root` hash.
+    pub fn verify(&self, leaf: CryptoHash, root: CryptoHash) -> bool {
+        let mut hash = leaf;
+        for level in &self.levels {
+            let Ok(index) = usize::try_from(level.index) else {
+                return false;
+            };
+            if index > level.siblings.len() {
+                return false;
+            }
+            let mut hashes = level.siblings.clone();
+            hashes.insert(index, hash);
+            hash = CryptoHash::new(&CryptoHashVec(hashes));
+        }
+        hash == root
+    }
+}
+
 /// The body of a block containing all the data included in the block.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct BlockBody {
@@ -356,18 +823,190 @@ pub struct BlockBody {
     pub operation_results: Vec<OperationResult>,
 }
 
+/// The hashes of the contents of a [`BlockBody`], as stored in the [`BlockHeader`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct BodyHashes {
+    bundles_hash: CryptoHash,
+    operations_hash: CryptoHash,
+    messages_hash: CryptoHash,
+    previous_message_blocks_hash: CryptoHash,
+    oracle_responses_hash: CryptoHash,
+    events_hash: CryptoHash,
+    blobs_hash: CryptoHash,
+    operation_results_hash: CryptoHash,
+}
+
+/// The hashes of an empty block body, which are the same for every empty block.
+static EMPTY_BODY_HASHES: LazyLock<BodyHashes> =
+    LazyLock::new(|| BlockBody::empty().compute_hashes());
+
+/// The weight of an operation in [`Block::gas_weight_hint`].
+const OPERATION_WEIGHT: u64 = 100;
+/// The weight of an incoming message in [`Block::gas_weight_hint`].
+const INCOMING_MESSAGE_WEIGHT: u64 = 50;
+/// The weight of an outgoing message in [`Block::gas_weight_hint`].
+const OUTGOING_MESSAGE_WEIGHT: u64 = 50;
+/// The weight of an oracle response in [`Block::gas_weight_hint`].
+const ORACLE_RESPONSE_WEIGHT: u64 = 200;
+/// The weight of a byte of a blob produced by the block in [`Block::gas_weight_hint`].
+const BLOB_BYTE_WEIGHT: u64 = 1;
+
+/// The counts of a block's contents that are relevant for fees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockResourceSummary {
+    /// The number of operations.
+    pub operations: usize,
+    /// The number of incoming messages.
+    pub incoming_messages: usize,
+    /// The number of outgoing messages.
+    pub outgoing_messages: usize,
+    /// The number of oracle responses.
+    pub oracle_responses: usize,
+    /// The number of blobs published by the operations.
+    pub published_blobs: usize,
+    /// The size of the serialized block body, in bytes.
+    pub body_size: usize,
+}
+
+impl BlockBody {
+    /// Returns the body of a block without any transactions.
+    pub fn empty() -> Self {
+        BlockBody {
+            incoming_bundles: Vec::new(),
+            operations: Vec::new(),
+            messages: Vec::new(),
+            previous_message_blocks: BTreeMap::new(),
+            oracle_responses: Vec::new(),
+            events: Vec::new(),
+            blobs: Vec::new(),
+            operation_results: Vec::new(),
+        }
+    }
+
+    /// Returns whether this body is equal to [`BlockBody::empty`]. The hashes of such a body
+    /// are precomputed.
+    pub fn is_canonically_empty(&self) -> bool {
+        self.incoming_bundles.is_empty()
+            && self.operations.is_empty()
+            && self.messages.is_empty()
+            && self.previous_message_blocks.is_empty()
+            && self.oracle_responses.is_empty()
+            && self.events.is_empty()
+            && self.blobs.is_empty()
+            && self.operation_results.is_empty()
+    }
+
+    /// Returns the hashes of the contents, using the precomputed ones if the body is empty.
+    fn hashes(&self) -> BodyHashes {
+        if self.is_canonically_empty() {
+            *EMPTY_BODY_HASHES
+        } else {
+            self.compute_hashes()
+        }
+    }
+
+    /// Computes the hashes of the contents.
+    fn compute_hashes(&self) -> BodyHashes {
+        BodyHashes {
+            bundles_hash: hashing::hash_vec(&self.incoming_bundles),
+            operations_hash: hashing::hash_vec(&self.operations),
+            messages_hash: hashing::hash_vec_vec(&self.messages),
+            previous_message_blocks_hash: CryptoHash::new(&PreviousMessageBlocksMap {
+                inner: Cow::Borrowed(&self.previous_message_blocks),
+            }),
+            oracle_responses_hash: hashing::hash_vec_vec(&self.oracle_responses),
+            events_hash: hashing::hash_vec_vec(&self.events),
+            blobs_hash: hashing::hash_vec_vec(&self.blobs),
+            operation_results_hash: hashing::hash_vec(&self.operation_results),
+        }
+    }
+
+    /// Returns the number of operations in this block for each application.
+    pub fn operation_count_by_application(&self) -> BTreeMap<GenericApplicationId, usize> {
+        let mut counts = BTreeMap::new();
+        for operation in &self.operations {
+            *counts.entry(operation.application_id()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns whether any operation in this block satisfies the predicate.
+    pub fn any_operation<F: Fn(&Operation) -> bool>(&self, f: F) -> bool {
+        self.operations.iter().any(f)
+    }
+
+    /// Returns the first operation in this block that satisfies the predicate, together with
+    /// its index among the block's operations.
+    pub fn find_operation<F: Fn(&Operation) -> bool>(&self, f: F) -> Option<(usize, &Operation)> {
+        self.operations
+            .iter()
+            .enumerate()
+            .find(|(_, operation)| f(operation))
+    }
+
+    /// Returns the number of transactions, i.e. incoming bundles and operations.
+    fn transaction_count(&self) -> usize {
+        self.incoming_bundles.len() + self.operations.len()
+    }
+
+    /// Returns whether the `index`th transaction has the same input and outcome in both bodies.
+    fn transaction_eq(&self, other: &BlockBody, index: usize) -> bool {
+        let operation_index = index.checked_sub(self.incoming_bundles.len());
+        let other_operation_index = index.checked_sub(other.incoming_bundles.len());
+        self.incoming_bundles.get(index) == other.incoming_bundles.get(index)
+            && operation_index.and_then(|index| self.operations.get(index))
+                == other_operation_index.and_then(|index| other.operations.get(index))
+            && operation_index.and_then(|index| self.operation_results.get(index))
+                == other_operation_index.and_then(|index| other.operation_results.get(index))
+            && self.messages.get(index) == other.messages.get(index)
+            && self.oracle_responses.get(index) == other.oracle_responses.get(index)
+            && self.events.get(index) == other.events.get(index)
+            && self.blobs.get(index) == other.blobs.get(index)
+    }
+}
+
+/// The differences between two blocks, as reported by [`Block::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockDiff {
+    /// The names of the header fields that differ.
+    pub header_fields: Vec<&'static str>,
+    /// The indices of the transactions whose input or outcome differs.
+    pub transactions: Vec<usize>,
+    /// Whether the hashes of the previous message blocks differ.
+    pub previous_message_blocks: bool,
+}
+
+impl BlockDiff {
+    /// Returns whether the blocks are identical.
+    pub fn is_empty(&self) -> bool {
+        self.header_fields.is_empty()
+            && self.transactions.is_empty()
+            && !self.previous_message_blocks
+    }
+}
+
 impl Block {
     pub fn new(block: ProposedBlock, outcome: BlockExecutionOutcome) -> Self {
-        let bundles_hash = hashing::hash_vec(&block.incoming_bundles);
-        let messages_hash = hashing::hash_vec_vec(&outcome.messages);
-        let previous_message_blocks_hash = CryptoHash::new(&PreviousMessageBlocksMap {
-            inner: Cow::Borrowed(&outcome.previous_message_blocks),
-        });
-        let operations_hash = hashing::hash_vec(&block.operations);
-        let oracle_responses_hash = hashing::hash_vec_vec(&outcome.oracle_responses);
-        let events_hash = hashing::hash_vec_vec(&outcome.events);
-        let blobs_hash = hashing::hash_vec_vec(&outcome.blobs);
-        let operation_results_hash = hashing::hash_vec(&outcome.operation_results);
+        let body = BlockBody {
+            incoming_bundles: block.incoming_bundles,
+            operations: block.operations,
+            messages: outcome.messages,
+            previous_message_blocks: outcome.previous_message_blocks,
+            oracle_responses: outcome.oracle_responses,
+            events: outcome.events,
+            blobs: outcome.blobs,
+            operation_results: outcome.operation_results,
+        };
+        let BodyHashes {
+            bundles_hash,
+            operations_hash,
+            messages_hash,
+            previous_message_blocks_hash,
+            oracle_responses_hash,
+            events_hash,
+            blobs_hash,
+            operation_results_hash,
+        } = body.hashes();
 
         let header = BlockHeader {
             chain_id: block.chain_id,
@@ -387,55 +1026,407 @@ impl Block {
             operation_results_hash,
         };
 
-        let body = BlockBody {
-            incoming_bundles: block.incoming_bundles,
-            operations: block.operations,
-            messages: outcome.messages,
-            previous_message_blocks: outcome.previous_message_blocks,
-            oracle_responses: outcome.oracle_responses,
-            events: outcome.events,
-            blobs: outcome.blobs,
-            operation_results: outcome.operation_results,
-        };
-
         Self { header, body }
     }
 
+    /// Returns a copy of this block with only its first `operation_count` operations.
+    ///
+    /// All incoming bundles are kept, since they are executed before the operations. The
+    /// outcomes of the removed operations are dropped, as are the previous message blocks of
+    /// recipients that no longer receive any message, and the header hashes are recomputed.
+    /// The state hash is kept unchanged, so it does not describe the result of executing the
+    /// prefix.
+    pub fn prefix(&self, operation_count: usize) -> Block {
+        let (mut block, mut outcome) = self.clone().into_proposal();
+        block.operations.truncate(operation_count);
+        let transaction_count = block.incoming_bundles.len() + block.operations.len();
+        outcome.messages.truncate(transaction_count);
+        outcome.oracle_responses.truncate(transaction_count);
+        outcome.events.truncate(transaction_count);
+        outcome.blobs.truncate(transaction_count);
+        outcome.operation_results.truncate(operation_count);
+        let recipients = outcome
+            .messages
+            .iter()
+            .flatten()
+            .flat_map(|message| message.destination.recipient())
+            .collect::<BTreeSet<_>>();
+        outcome
+            .previous_message_blocks
+            .retain(|recipient, _| recipients.contains(recipient));
+        Block::new(block, outcome)
+    }
+
+    /// Returns the BCS serialization of the block, preceded by a magic prefix and the
+    /// format version, so that the format can be changed later.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = BLOCK_BYTES_MAGIC.to_vec();
+        bytes.push(BLOCK_BYTES_VERSION);
+        bcs::serialize_into(&mut bytes, self).expect("Block serialization should not fail");
+        bytes
+    }
+
+    /// Decodes a block written by [`Block::to_canonical_bytes`].
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Block, BlockDecodingError> {
+        let bytes = bytes
+            .strip_prefix(&BLOCK_BYTES_MAGIC)
+            .ok_or(BlockDecodingError::MissingMagic)?;
+        let (version, bytes) = bytes
+            .split_first()
+            .ok_or(BlockDecodingError::MissingMagic)?;
+        match *version {
+            BLOCK_BYTES_VERSION => Ok(bcs::from_bytes(bytes)?),
+            version => Err(BlockDecodingError::UnknownVersion(version)),
+        }
+    }
+
+    /// Checks that this block directly follows `parent` on the same chain: it must refer to
+    /// the parent's hash, be at the next height and not have an earlier timestamp.
+    pub fn follows(&self, parent: &Hashed<Block>) -> Result<(), ChainError> {
+        let header = &self.header;
+        let parent_header = &parent.inner().header;
+        ensure!(
+            header.chain_id == parent_header.chain_id,
+            ChainError::UnexpectedChainId {
+                expected: parent_header.chain_id,
+                found: header.chain_id,
+            }
+        );
+        let expected_block_height = parent_header.height.try_add_one()?;
+        ensure!(
+            header.height == expected_block_height,
+            ChainError::UnexpectedBlockHeight {
+                expected_block_height,
+                found_block_height: header.height,
+            }
+        );
+        ensure!(
+            header.previous_block_hash == Some(parent.hash()),
+            ChainError::UnexpectedPreviousBlockHash
+        );
+        ensure!(
+            header.timestamp >= parent_header.timestamp,
+            ChainError::InvalidBlockTimestamp
+        );
+        Ok(())
+    }
+
+    /// Returns the header together with the hash of the whole block, so that the full
+    /// block can later be matched against them.
+    pub fn header_with_commitment(&self) -> (BlockHeader, CryptoHash) {
+        (self.header.clone(), CryptoHash::new(self))
+    }
+
+    /// Checks that the hashes in the header match the contents of the body.
+    pub fn validate_hashes(&self) -> Result<(), ChainError> {
+        self.header.validate_body_hashes(&self.body, true)
+    }
+
+    /// Checks that the incoming bundles from each origin are in the order they were produced
+    /// by the sending chain, i.e. that their `(height, transaction_index)` cursors are
+    /// strictly increasing. Bundles may be skipped.
+    pub fn validate_incoming_bundle_order(&self) -> Result<(), ChainError> {
+        let mut last_cursors = BTreeMap::new();
+        for IncomingBundle { origin, bundle, .. } in &self.body.incoming_bundles {
+            let cursor = (bundle.height, bundle.transaction_index);
+            if let Some(previous) = last_cursors.insert(origin, cursor) {
+                ensure!(
+                    cursor > previous,
+                    ChainError::IncomingBundleOutOfOrder {
+                        chain_id: self.header.chain_id,
+                        origin: Box::new(origin.clone()),
+                        previous_height: previous.0,
+                        previous_index: previous.1,
+                        height: bundle.height,
+                        index: bundle.transaction_index,
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the oracle responses from this block, e.g. to save bandwidth when sending it
+    /// to a light client. The header still commits to them.
+    pub fn redact_oracle_responses(mut self) -> RedactedBlock {
+        self.body.oracle_responses.clear();
+        RedactedBlock {
+            header: self.header,
+            body: self.body,
+        }
+    }
+
+    /// Returns which header fields and which transactions differ between this block and
+    /// `other`. Transactions that only exist in one of the blocks are reported as different.
+    pub fn diff(&self, other: &Block) -> BlockDiff {
+        let (header, other_header) = (&self.header, &other.header);
+        let header_fields = [
+            ("chain_id", header.chain_id == other_header.chain_id),
+            ("epoch", header.epoch == other_header.epoch),
+            ("height", header.height == other_header.height),
+            ("timestamp", header.timestamp == other_header.timestamp),
+            ("state_hash", header.state_hash == other_header.state_hash),
+            (
+                "previous_block_hash",
+                header.previous_block_hash == other_header.previous_block_hash,
+            ),
+            (
+                "authenticated_signer",
+                header.authenticated_signer == other_header.authenticated_signer,
+            ),
+            (
+                "bundles_hash",
+                header.bundles_hash == other_header.bundles_hash,
+            ),
+            (
+                "operations_hash",
+                header.operations_hash == other_header.operations_hash,
+            ),
+            (
+                "messages_hash",
+                header.messages_hash == other_header.messages_hash,
+            ),
+            (
+                "previous_message_blocks_hash",
+                header.previous_message_blocks_hash == other_header.previous_message_blocks_hash,
+            ),
+            (
+                "oracle_responses_hash",
+                header.oracle_responses_hash == other_header.oracle_responses_hash,
+            ),
+            (
+                "events_hash",
+                header.events_hash == other_header.events_hash,
+            ),
+            ("blobs_hash", header.blobs_hash == other_header.blobs_hash),
+            (
+                "operation_results_hash",
+                header.operation_results_hash == other_header.operation_results_hash,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, equal)| !equal)
+        .map(|(field, _)| field)
+        .collect();
+        let (body, other_body) = (&self.body, &other.body);
+        let transaction_count = body.transaction_count().max(other_body.transaction_count());
+        let transactions = (0..transaction_count)
+            .filter(|index| !body.transaction_eq(other_body, *index))
+            .collect();
+        BlockDiff {
+            header_fields,
+            transactions,
+            previous_message_blocks: body.previous_message_blocks
+                != other_body.previous_message_blocks,
+        }
+    }
+
     /// Returns the bundles of messages sent via the given medium to the specified
     /// recipient. Messages originating from different transactions of the original block
     /// are kept in separate bundles. If the medium is a channel, does not verify that the
     /// recipient is actually subscribed to that channel.
+    ///
+    /// Messages are only included up to the last transaction whose messages all have an index
+    /// that fits into a `u32`, i.e. all of them unless [`Block::checked_message_count`] is
+    /// `None`.
     pub fn message_bundles_for<'a>(
         &'a self,
         medium: &'a Medium,
         recipient: ChainId,
         certificate_hash: CryptoHash,
     ) -> impl Iterator<Item = (Epoch, MessageBundle)> + 'a {
-        let mut index = 0u32;
+        self.filtered_message_bundles_for(
+            medium,
+            recipient,
+            certificate_hash,
+            None,
+            self.indexed_transaction_messages(),
+        )
+    }
+
+    /// Returns the bundles of messages sent via the given medium to the specified
+    /// recipient, like [`Block::message_bundles_for`]. If the medium is a channel, returns
+    /// nothing unless `is_subscribed` returns `true` for the channel name and recipient.
+    pub fn message_bundles_for_subscriber<'a>(
+        &'a self,
+        medium: &'a Medium,
+        recipient: ChainId,
+        certificate_hash: CryptoHash,
+        is_subscribed: impl Fn(&ChannelName, ChainId) -> bool,
+    ) -> impl Iterator<Item = (Epoch, MessageBundle)> + 'a {
+        let subscribed = match medium {
+            Medium::Direct => true,
+            Medium::Channel(full_name) => is_subscribed(&full_name.name, recipient),
+        };
+        subscribed
+            .then(|| {
+                self.filtered_message_bundles_for(
+                    medium,
+                    recipient,
+                    certificate_hash,
+                    None,
+                    self.indexed_transaction_messages(),
+                )
+            })
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the messages sent via the given medium to the specified recipient, merged into
+    /// a single bundle. Each message keeps its index in the block, so identical messages from
+    /// different transactions remain distinct.
+    ///
+    /// Unlike [`Block::message_bundles_for`], the result doesn't record which transaction
+    /// each message came from: the bundle has the transaction index of the first one. It is
+    /// meant for transport, and must be split again before it is added to an inbox.
+    pub fn coalesced_bundles_for(
+        &self,
+        medium: &Medium,
+        recipient: ChainId,
+        certificate_hash: CryptoHash,
+    ) -> Vec<MessageBundle> {
+        let mut bundles = self
+            .message_bundles_for(medium, recipient, certificate_hash)
+            .map(|(_, bundle)| bundle);
+        let Some(mut coalesced) = bundles.next() else {
+            return Vec::new();
+        };
+        for bundle in bundles {
+            coalesced.messages.extend(bundle.messages);
+        }
+        vec![coalesced]
+    }
+
+    /// Returns the same bundles as [`Block::message_bundles_for`], but starting with the
+    /// last transaction of the block. The messages keep their index in the block.
+    pub fn message_bundles_for_rev<'a>(
+        &'a self,
+        medium: &'a Medium,
+        recipient: ChainId,
+        certificate_hash: CryptoHash,
+    ) -> impl Iterator<Item = (Epoch, MessageBundle)> + 'a {
+        let mut transactions = self.indexed_transaction_messages().collect::<Vec<_>>();
+        transactions.reverse();
+        self.filtered_message_bundles_for(
+            medium,
+            recipient,
+            certificate_hash,
+            None,
+            transactions.into_iter(),
+        )
+    }
+
+    /// Returns the bundles of messages of the given kind sent via the given medium to the
+    /// specified recipient, like [`Block::message_bundles_for`]. The messages keep their
+    /// index in the block.
+    pub fn message_bundles_for_kind<'a>(
+        &'a self,
+        medium: &'a Medium,
+        recipient: ChainId,
+        certificate_hash: CryptoHash,
+        kind: MessageKind,
+    ) -> impl Iterator<Item = (Epoch, MessageBundle)> + 'a {
+        self.filtered_message_bundles_for(
+            medium,
+            recipient,
+            certificate_hash,
+            Some(kind),
+            self.indexed_transaction_messages(),
+        )
+    }
+
+    /// Returns the index of each transaction together with the index of its first outgoing
+    /// message in the block, and its outgoing messages.
+    ///
+    /// Message indices are `u32`s, so this stops before the first transaction whose messages
+    /// can't all be given an index; see [`Block::checked_message_count`].
+    fn indexed_transaction_messages(
+        &self,
+    ) -> impl Iterator<Item = (u32, u32, &Vec<OutgoingMessage>)> + '_ {
+        let mut next_index = Some(0u32);
+        (0u32..)
+            .zip(self.messages())
+            .map_while(move |(transaction_index, txn_messages)| {
+                let index = next_index?;
+                next_index = checked_u32_sum([index as usize, txn_messages.len()]);
+                next_index.map(|_| (transaction_index, index, txn_messages))
+            })
+    }
+
+    /// Returns the bundles for the given transactions, as returned by
+    /// [`Block::indexed_transaction_messages`], in the order they are given.
+    fn filtered_message_bundles_for<'a>(
+        &'a self,
+        medium: &'a Medium,
+        recipient: ChainId,
+        certificate_hash: CryptoHash,
+        kind: Option<MessageKind>,
+        transactions: impl Iterator<Item = (u32, u32, &'a Vec<OutgoingMessage>)> + 'a,
+    ) -> impl Iterator<Item = (Epoch, MessageBundle)> + 'a {
         let block_height = self.header.height;
         let block_timestamp = self.header.timestamp;
         let block_epoch = self.header.epoch;
 
-        (0u32..)
-            .zip(self.messages())
-            .filter_map(move |(transaction_index, txn_messages)| {
-                let messages = (index..)
-                    .zip(txn_messages)
-                    .filter(|(_, message)| message.has_destination(medium, recipient))
-                    .map(|(idx, message)| message.clone().into_posted(idx))
-                    .collect::<Vec<_>>();
-                index += txn_messages.len() as u32;
-                (!messages.is_empty()).then(|| {
-                    let bundle = MessageBundle {
-                        height: block_height,
-                        timestamp: block_timestamp,
-                        certificate_hash,
-                        transaction_index,
-                        messages,
-                    };
-                    (block_epoch, bundle)
-                })
+        transactions.filter_map(move |(transaction_index, index, txn_messages)| {
+            let messages = (index..)
+                .zip(txn_messages)
+                .filter(|(_, message)| message.has_destination(medium, recipient))
+                .filter(|(_, message)| kind.is_none_or(|kind| message.kind == kind))
+                .map(|(idx, message)| message.clone().into_posted(idx))
+                .collect::<Vec<_>>();
+            (!messages.is_empty()).then(|| {
+                let bundle = MessageBundle {
+                    height: block_height,
+                    timestamp: block_timestamp,
+                    certificate_hash,
+                    transaction_index,
+                    messages,
+                };
+                (block_epoch, bundle)
             })
+        })
+    }
+
+    /// Returns an iterator over all transactions, by index: first the incoming bundles, then
+    /// the operations.
+    pub fn transactions(&self) -> impl Iterator<Item = (u32, Transaction<'_>)> {
+        let bundles = self
+            .body
+            .incoming_bundles
+            .iter()
+            .map(Transaction::ReceiveMessages);
+        let operations = self
+            .body
+            .operations
+            .iter()
+            .map(Transaction::ExecuteOperation);
+        (0u32..).zip(bundles.chain(operations))
+    }
+
+    /// Returns the transaction with the given index, as numbered by [`Block::transactions`],
+    /// or `None` if there is no such transaction.
+    pub fn transaction_at(&self, index: u32) -> Option<Transaction<'_>> {
+        let index = usize::try_from(index).ok()?;
+        let bundle_count = self.body.incoming_bundles.len();
+        match index.checked_sub(bundle_count) {
+            None => Some(Transaction::ReceiveMessages(
+                &self.body.incoming_bundles[index],
+            )),
+            Some(operation_index) => self
+                .body
+                .operations
+                .get(operation_index)
+                .map(Transaction::ExecuteOperation),
+        }
+    }
+
+    /// Returns the oracle responses recorded for the transaction with the given index, as
+    /// numbered by [`Block::transactions`], or `None` if there is no such transaction.
+    pub fn oracle_responses_for(&self, transaction_index: usize) -> Option<&[OracleResponse]> {
+        self.body
+            .oracle_responses
+            .get(transaction_index)
+            .map(Vec::as_slice)
     }
 
     /// Returns the `message_index`th outgoing message created by the `operation_index`th operation,
@@ -445,22 +1436,71 @@ impl Block {
         operation_index: usize,
         message_index: u32,
     ) -> Option<MessageId> {
+        self.try_message_id_for_operation(operation_index, message_index)
+            .ok()
+    }
+
+    /// Returns the `message_index`th outgoing message created by the `operation_index`th operation,
+    /// or an error explaining why there is no such message.
+    pub fn try_message_id_for_operation(
+        &self,
+        operation_index: usize,
+        message_index: u32,
+    ) -> Result<MessageId, MessageIdError> {
         let block = &self.body;
-        let transaction_index = block.incoming_bundles.len().checked_add(operation_index)?;
-        if message_index >= u32::try_from(self.body.messages.get(transaction_index)?.len()).ok()? {
-            return None;
-        }
+        ensure!(
+            operation_index < block.operations.len(),
+            MessageIdError::OperationOutOfRange {
+                requested: operation_index,
+                available: block.operations.len(),
+            }
+        );
+        let transaction_index = block.incoming_bundles.len() + operation_index;
+        let message_count = block.messages.get(transaction_index).map_or(0, Vec::len);
+        ensure!(
+            usize::try_from(message_index).is_ok_and(|index| index < message_count),
+            MessageIdError::MessageOutOfRange {
+                requested: message_index,
+                available: message_count,
+            }
+        );
         let first_message_index = u32::try_from(
-            self.body
+            block
                 .messages
                 .iter()
                 .take(transaction_index)
                 .map(Vec::len)
                 .sum::<usize>(),
         )
-        .ok()?;
-        let index = first_message_index.checked_add(message_index)?;
-        Some(self.message_id(index))
+        .map_err(|_| MessageIdError::IndexOverflow)?;
+        let index = first_message_index
+            .checked_add(message_index)
+            .ok_or(MessageIdError::IndexOverflow)?;
+        Ok(self.message_id(index))
+    }
+
+    /// Returns a proof that the `index`th operation is included in this block, relative to
+    /// the header's `operations_hash`, or `None` if there is no such operation.
+    pub fn merkle_proof_for_operation(&self, index: usize) -> Option<MerkleProof> {
+        let leaves = hashing::leaf_hashes(&self.body.operations);
+        MerkleProof::from_levels(vec![(index, leaves)])
+    }
+
+    /// Returns a proof that the `message_index`th outgoing message of the `transaction_index`th
+    /// transaction is included in this block, relative to the header's `messages_hash`, or
+    /// `None` if there is no such message.
+    pub fn merkle_proof_for_message(
+        &self,
+        transaction_index: usize,
+        message_index: usize,
+    ) -> Option<MerkleProof> {
+        let messages = self.body.messages.get(transaction_index)?;
+        let leaves = hashing::leaf_hashes(messages);
+        let transaction_hashes = self.body.messages.iter().map(hashing::hash_vec).collect();
+        MerkleProof::from_levels(vec![
+            (message_index, leaves),
+            (transaction_index, transaction_hashes),
+        ])
     }
 
     /// Returns the message ID belonging to the `index`th outgoing message in this block.
@@ -472,24 +1512,121 @@ impl Block {
         }
     }
 
+    /// Returns the number of outgoing messages in this block, or `None` if it doesn't fit
+    /// into a `u32`, so that not all messages can be given an index.
+    pub fn checked_message_count(&self) -> Option<u32> {
+        checked_u32_sum(self.body.messages.iter().map(Vec::len))
+    }
+
+    /// Returns the IDs of all outgoing messages in this block, in order.
+    pub fn all_message_ids(&self) -> Vec<MessageId> {
+        let message_count = self.messages().iter().map(Vec::len).sum::<usize>();
+        (0u32..)
+            .take(message_count)
+            .map(|index| self.message_id(index))
+            .collect()
+    }
+
+    /// Returns an iterator over all outgoing messages in this block, together with their ID.
+    pub fn outgoing_messages_with_ids(
+        &self,
+    ) -> impl Iterator<Item = (MessageId, &OutgoingMessage)> + '_ {
+        (0u32..)
+            .zip(self.messages().iter().flatten())
+            .map(|(index, message)| (self.message_id(index), message))
+    }
+
+    /// Consumes the block and returns all of its outgoing messages, together with their ID.
+    /// This is the owned counterpart of [`Block::outgoing_messages_with_ids`].
+    pub fn into_flat_messages(self) -> Vec<(MessageId, OutgoingMessage)> {
+        let Block { header, body } = self;
+        (0u32..)
+            .zip(body.messages.into_iter().flatten())
+            .map(|(index, message)| {
+                let message_id = MessageId {
+                    chain_id: header.chain_id,
+                    height: header.height,
+                    index,
+                };
+                (message_id, message)
+            })
+            .collect()
+    }
+
+    /// Returns the outgoing messages in this block that are sent directly to a chain, grouped
+    /// by recipient and in the order they were sent. Messages to channel subscribers have no
+    /// single recipient and are skipped.
+    pub fn messages_by_recipient(&self) -> BTreeMap<ChainId, Vec<(MessageId, &OutgoingMessage)>> {
+        let mut messages_by_recipient = BTreeMap::<_, Vec<_>>::new();
+        for (message_id, message) in self.outgoing_messages_with_ids() {
+            if let Destination::Recipient(recipient) = message.destination {
+                messages_by_recipient
+                    .entry(recipient)
+                    .or_default()
+                    .push((message_id, message));
+            }
+        }
+        messages_by_recipient
+    }
+
+    /// Returns all events produced by this block, in order, together with their index among
+    /// all of the block's events.
+    pub fn flattened_events(&self) -> Vec<(u32, &Event)> {
+        (0u32..).zip(self.body.events.iter().flatten()).collect()
+    }
+
     /// Returns the outgoing message with the specified id, or `None` if there is no such message.
     pub fn message_by_id(&self, message_id: &MessageId) -> Option<&OutgoingMessage> {
-        let MessageId {
-            chain_id,
-            height,
-            index,
-        } = message_id;
-        if self.header.chain_id != *chain_id || self.header.height != *height {
-            return None;
-        }
-        let mut index = usize::try_from(*index).ok()?;
-        for messages in self.messages() {
-            if let Some(message) = messages.get(index) {
-                return Some(message);
+        self.try_message_by_id(message_id).ok()
+    }
+
+    /// Returns the outgoing message with the specified id, or an error explaining why there is
+    /// no such message.
+    pub fn try_message_by_id(
+        &self,
+        message_id: &MessageId,
+    ) -> Result<&OutgoingMessage, MessageLookupError> {
+        let header = &self.header;
+        ensure!(
+            header.chain_id == message_id.chain_id,
+            MessageLookupError::WrongChain {
+                requested: message_id.chain_id,
+                block: header.chain_id,
             }
-            index -= messages.len();
-        }
-        None
+        );
+        ensure!(
+            header.height == message_id.height,
+            MessageLookupError::WrongHeight {
+                requested: message_id.height,
+                block: header.height,
+            }
+        );
+        usize::try_from(message_id.index)
+            .ok()
+            .and_then(|index| self.outgoing_messages_with_ids().nth(index))
+            .map(|(_, message)| message)
+            .ok_or_else(|| MessageLookupError::IndexOutOfRange {
+                requested: message_id.index,
+                available: self.messages().iter().map(Vec::len).sum(),
+            })
+    }
+
+    /// Returns the distinct chains that sent the incoming bundles in this block.
+    pub fn incoming_bundle_senders(&self) -> BTreeSet<ChainId> {
+        self.body
+            .incoming_bundles
+            .iter()
+            .map(|bundle| bundle.origin.sender)
+            .collect()
+    }
+
+    /// Returns the first incoming bundle in this block from the given origin and height, or
+    /// `None` if there is no such bundle.
+    pub fn incoming_bundle(&self, origin: &Origin, height: BlockHeight) -> Option<&IncomingBundle> {
+        self.body
+            .incoming_bundles
+            .iter()
+            .find(|bundle| bundle.origin == *origin && bundle.bundle.height == height)
     }
 
     /// Returns all the blob IDs required by this block.
@@ -517,6 +1654,21 @@ impl Block {
             .collect()
     }
 
+    /// Returns the IDs of all applications created by this block, which are read from the
+    /// created application description blobs.
+    pub fn published_application_ids(&self) -> Result<BTreeSet<ApplicationId>, bcs::Error> {
+        self.body
+            .blobs
+            .iter()
+            .flatten()
+            .filter(|blob| blob.content().blob_type() == BlobType::ApplicationDescription)
+            .map(|blob| {
+                let description = bcs::from_bytes::<ApplicationDescription>(blob.bytes())?;
+                Ok(ApplicationId::from(&description))
+            })
+            .collect()
+    }
+
     /// Returns all the blob IDs created by the block's operations.
     pub fn created_blob_ids(&self) -> BTreeSet<BlobId> {
         self.body
@@ -551,6 +1703,143 @@ impl Block {
         required_blob_ids
     }
 
+    /// Returns the IDs of all events this block publishes or reads through oracle calls.
+    pub fn required_event_ids(&self) -> HashSet<EventId> {
+        let published = self
+            .body
+            .events
+            .iter()
+            .flatten()
+            .map(|event| event.id(self.header.chain_id));
+        let read =
+            self.body
+                .oracle_responses
+                .iter()
+                .flatten()
+                .filter_map(|response| match response {
+                    OracleResponse::Event(event_id, _) => Some(event_id.clone()),
+                    _ => None,
+                });
+        published.chain(read).collect()
+    }
+
+    /// Returns the counts of the block's contents that are relevant for fees, and the size of
+    /// its serialized body.
+    ///
+    /// The counts are collected in a single pass over the transactions, and the body is
+    /// serialized once to measure its size. This fails only if BCS cannot serialize the body,
+    /// e.g. because one of its sequences is longer than BCS allows.
+    pub fn fee_relevant_summary(&self) -> Result<BlockResourceSummary, bcs::Error> {
+        let mut summary = BlockResourceSummary {
+            body_size: bcs::serialized_size(&self.body)?,
+            ..BlockResourceSummary::default()
+        };
+        let mut published_blob_ids = BTreeSet::new();
+        for (index, (_, transaction)) in self.transactions().enumerate() {
+            match transaction {
+                Transaction::ReceiveMessages(bundle) => {
+                    summary.incoming_messages += bundle.bundle.messages.len();
+                }
+                Transaction::ExecuteOperation(operation) => {
+                    summary.operations += 1;
+                    published_blob_ids.extend(operation.published_blob_ids());
+                }
+            }
+            summary.outgoing_messages += self.body.messages.get(index).map_or(0, Vec::len);
+            summary.oracle_responses += self.body.oracle_responses.get(index).map_or(0, Vec::len);
+        }
+        summary.published_blobs = published_blob_ids.len();
+        Ok(summary)
+    }
+
+    /// Returns a rough estimate of the relative cost of executing this block.
+    ///
+    /// This is a weighted sum of the numbers of operations, incoming and outgoing messages
+    /// and oracle responses, and of the size of the blobs produced by the block. It is only
+    /// a heuristic, e.g. for deciding how many blocks to process together, and not related to
+    /// the fees actually charged.
+    pub fn gas_weight_hint(&self) -> u64 {
+        let incoming_messages: usize = self
+            .body
+            .incoming_bundles
+            .iter()
+            .map(|bundle| bundle.bundle.messages.len())
+            .sum();
+        let outgoing_messages: usize = self.body.messages.iter().map(Vec::len).sum();
+        let blob_bytes: usize = self
+            .body
+            .blobs
+            .iter()
+            .flatten()
+            .map(|blob| blob.bytes().len())
+            .sum();
+        [
+            (self.body.operations.len(), OPERATION_WEIGHT),
+            (incoming_messages, INCOMING_MESSAGE_WEIGHT),
+            (outgoing_messages, OUTGOING_MESSAGE_WEIGHT),
+            (self.oracle_response_count(), ORACLE_RESPONSE_WEIGHT),
+            (blob_bytes, BLOB_BYTE_WEIGHT),
+        ]
+        .into_iter()
+        .fold(0u64, |total, (count, weight)| {
+            total.saturating_add((count as u64).saturating_mul(weight))
+        })
+    }
+
+    /// Returns the owner who authorized this block, if any.
+    pub fn author(&self) -> Option<AccountOwner> {
+        self.header.authenticated_signer
+    }
+
+    /// Returns whether this block was authorized by the given owner.
+    pub fn is_authored_by(&self, owner: &AccountOwner) -> bool {
+        self.author().as_ref() == Some(owner)
+    }
+
+    /// Returns whether this block needs an authenticated signer, i.e. whether it has any
+    /// operations. Blocks that only receive messages don't.
+    pub fn requires_authenticated_signer(&self) -> bool {
+        !self.body.operations.is_empty()
+    }
+
+    /// Returns an error if this block has operations but no authenticated signer.
+    pub fn check_authentication(&self) -> Result<(), ChainError> {
+        ensure!(
+            !self.requires_authenticated_signer() || self.header.authenticated_signer.is_some(),
+            ChainError::MissingAuthenticatedSigner
+        );
+        Ok(())
+    }
+
+    /// Checks that the system operations acting on behalf of an owner name the block's
+    /// authenticated signer as that owner: this applies to every `Claim` and to every
+    /// `Transfer` that is not from the chain's own account.
+    ///
+    /// Transfers from the chain's account are authorized by the chain ownership instead, and
+    /// user operations are opaque, so neither is checked here.
+    pub fn check_signer_consistency(&self) -> Result<(), ChainError> {
+        let signer = self.header.authenticated_signer;
+        for (index, operation) in self.body.operations.iter().enumerate() {
+            let Operation::System(operation) = operation else {
+                continue;
+            };
+            let owner = match operation.as_ref() {
+                SystemOperation::Transfer { owner, .. } if *owner != AccountOwner::CHAIN => *owner,
+                SystemOperation::Claim { owner, .. } => *owner,
+                _ => continue,
+            };
+            ensure!(
+                signer == Some(owner),
+                ChainError::OperationSignerMismatch {
+                    index,
+                    owner,
+                    signer
+                }
+            );
+        }
+        Ok(())
+    }
+
     /// Returns reference to the outgoing messages in the block.
     pub fn messages(&self) -> &Vec<Vec<OutgoingMessage>> {
         &self.body.messages
@@ -564,6 +1853,36 @@ impl Block {
             .any(|responses| !responses.is_empty())
     }
 
+    /// Returns the number of oracle responses in this block.
+    pub fn oracle_response_count(&self) -> usize {
+        self.body.oracle_responses.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the number of oracle responses in this block for each kind of response.
+    pub fn oracle_response_counts(&self) -> BTreeMap<OracleResponseKind, usize> {
+        let mut counts = BTreeMap::new();
+        for response in self.body.oracle_responses.iter().flatten() {
+            *counts.entry(response.kind()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns whether this block has neither operations nor incoming bundles.
+    pub fn is_empty(&self) -> bool {
+        self.body.operations.is_empty() && self.body.incoming_bundles.is_empty()
+    }
+
+    /// Returns whether this block sends any messages, emits any events or records any
+    /// oracle responses.
+    pub fn has_side_effects(&self) -> bool {
+        self.body
+            .messages
+            .iter()
+            .any(|messages| !messages.is_empty())
+            || self.body.events.iter().any(|events| !events.is_empty())
+            || self.has_oracle_responses()
+    }
+
     /// Returns whether this block matches the proposal.
     pub fn matches_proposed_block(&self, block: &ProposedBlock) -> bool {
         let ProposedBlock {
@@ -652,6 +1971,188 @@ impl Block {
     }
 }
 
+/// Failure to build a consistent [`Block`] with a [`BlockBuilder`].
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum BlockBuilderError {
+    /// An incoming bundle was added after an operation.
+    #[error("Incoming bundles must be added before operations")]
+    IncomingBundleAfterOperation,
+
+    /// A transaction outcome was added before any transaction.
+    #[error("Transaction outcomes must be added after their transaction")]
+    MissingTransaction,
+
+    /// An operation result was added for an incoming bundle.
+    #[error("Operation results can only be added after an operation")]
+    MissingOperation,
+}
+
+/// A builder for a [`Block`], whose header hashes are computed from the body.
+///
+/// Transactions are added with [`BlockBuilder::with_incoming_bundle`] and
+/// [`BlockBuilder::with_operation`]. The `with_transaction_*` methods set the outcome of the
+/// last added transaction.
+#[derive(Clone, Debug)]
+pub struct BlockBuilder {
+    block: ProposedBlock,
+    outcome: BlockExecutionOutcome,
+    error: Option<BlockBuilderError>,
+}
+
+impl BlockBuilder {
+    /// Starts building the first block of the given chain, with the given execution state
+    /// hash.
+    pub fn new(chain_id: ChainId, state_hash: CryptoHash) -> Self {
+        BlockBuilder {
+            block: ProposedBlock {
+                chain_id,
+                epoch: Epoch::ZERO,
+                incoming_bundles: Vec::new(),
+                operations: Vec::new(),
+                height: BlockHeight::ZERO,
+                timestamp: Timestamp::from(0),
+                authenticated_signer: None,
+                previous_block_hash: None,
+            },
+            outcome: BlockExecutionOutcome {
+                messages: Vec::new(),
+                previous_message_blocks: BTreeMap::new(),
+                state_hash,
+                oracle_responses: Vec::new(),
+                events: Vec::new(),
+                blobs: Vec::new(),
+                operation_results: Vec::new(),
+            },
+            error: None,
+        }
+    }
+
+    /// Sets the epoch.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.block.epoch = epoch;
+        self
+    }
+
+    /// Sets the height and the hash of the previous block.
+    pub fn with_height(mut self, height: BlockHeight, previous_block_hash: CryptoHash) -> Self {
+        self.block.height = height;
+        self.block.previous_block_hash = Some(previous_block_hash);
+        self
+    }
+
+    /// Sets the timestamp.
+    pub fn with_timestamp(mut self, timestamp: impl Into<Timestamp>) -> Self {
+        self.block.timestamp = timestamp.into();
+        self
+    }
+
+    /// Sets the authenticated signer.
+    pub fn with_authenticated_signer(mut self, authenticated_signer: AccountOwner) -> Self {
+        self.block.authenticated_signer = Some(authenticated_signer);
+        self
+    }
+
+    /// Records the hash of the previous block that sent messages to the given recipient.
+    pub fn with_previous_message_block(mut self, recipient: ChainId, hash: CryptoHash) -> Self {
+        self.outcome.previous_message_blocks.insert(recipient, hash);
+        self
+    }
+
+    /// Adds an incoming bundle as the next transaction. All incoming bundles must be added
+    /// before the operations.
+    pub fn with_incoming_bundle(mut self, incoming_bundle: IncomingBundle) -> Self {
+        if !self.block.operations.is_empty() {
+            self.error
+                .get_or_insert(BlockBuilderError::IncomingBundleAfterOperation);
+        }
+        self.block.incoming_bundles.push(incoming_bundle);
+        self.push_transaction_outcome();
+        self
+    }
+
+    /// Adds an operation as the next transaction.
+    pub fn with_operation(mut self, operation: impl Into<Operation>) -> Self {
+        self.block.operations.push(operation.into());
+        self.outcome
+            .operation_results
+            .push(OperationResult::default());
+        self.push_transaction_outcome();
+        self
+    }
+
+    /// Sets the outgoing messages of the last transaction.
+    pub fn with_transaction_messages(mut self, messages: Vec<OutgoingMessage>) -> Self {
+        if let Some(last) = self.last_transaction_outcome(|outcome| &mut outcome.messages) {
+            *last = messages;
+        }
+        self
+    }
+
+    /// Sets the oracle responses of the last transaction.
+    pub fn with_transaction_oracle_responses(mut self, responses: Vec<OracleResponse>) -> Self {
+        if let Some(last) = self.last_transaction_outcome(|outcome| &mut outcome.oracle_responses) {
+            *last = responses;
+        }
+        self
+    }
+
+    /// Sets the events of the last transaction.
+    pub fn with_transaction_events(mut self, events: Vec<Event>) -> Self {
+        if let Some(last) = self.last_transaction_outcome(|outcome| &mut outcome.events) {
+            *last = events;
+        }
+        self
+    }
+
+    /// Sets the blobs created by the last transaction.
+    pub fn with_transaction_blobs(mut self, blobs: Vec<Blob>) -> Self {
+        if let Some(last) = self.last_transaction_outcome(|outcome| &mut outcome.blobs) {
+            *last = blobs;
+        }
+        self
+    }
+
+    /// Sets the result of the last transaction, which must be an operation.
+    pub fn with_operation_result(mut self, result: OperationResult) -> Self {
+        // Since incoming bundles come first, the last transaction is the last operation, if any.
+        match self.outcome.operation_results.last_mut() {
+            Some(last) => *last = result,
+            None => {
+                self.error
+                    .get_or_insert(BlockBuilderError::MissingOperation);
+            }
+        }
+        self
+    }
+
+    /// Returns the block, or the first error encountered while building it.
+    pub fn build(self) -> Result<Block, BlockBuilderError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(Block::new(self.block, self.outcome))
+    }
+
+    fn push_transaction_outcome(&mut self) {
+        self.outcome.messages.push(Vec::new());
+        self.outcome.oracle_responses.push(Vec::new());
+        self.outcome.events.push(Vec::new());
+        self.outcome.blobs.push(Vec::new());
+    }
+
+    fn last_transaction_outcome<T>(
+        &mut self,
+        field: impl FnOnce(&mut BlockExecutionOutcome) -> &mut Vec<Vec<T>>,
+    ) -> Option<&mut Vec<T>> {
+        let last = field(&mut self.outcome).last_mut();
+        if last.is_none() {
+            self.error
+                .get_or_insert(BlockBuilderError::MissingTransaction);
+        }
+        last
+    }
+}
+
 impl BcsHashable<'_> for Block {}
 
 #[derive(Serialize, Deserialize)]
@@ -676,8 +2177,12 @@ struct SerializedHeader {
 mod hashing {
     use linera_base::crypto::{BcsHashable, CryptoHash, CryptoHashVec};
 
+    pub(super) fn leaf_hashes<'de, T: BcsHashable<'de>>(it: impl AsRef<[T]>) -> Vec<CryptoHash> {
+        it.as_ref().iter().map(CryptoHash::new).collect()
+    }
+
     pub(super) fn hash_vec<'de, T: BcsHashable<'de>>(it: impl AsRef<[T]>) -> CryptoHash {
-        let v = CryptoHashVec(it.as_ref().iter().map(CryptoHash::new).collect::<Vec<_>>());
+        let v = CryptoHashVec(leaf_hashes(it));
         CryptoHash::new(&v)
     }
 
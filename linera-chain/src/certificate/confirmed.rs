@@ -56,7 +56,7 @@ impl TryFrom<Certificate> for GenericCertificate<ConfirmedBlock> {
     fn try_from(cert: Certificate) -> Result<Self, Self::Error> {
         match cert {
             Certificate::Confirmed(confirmed) => Ok(confirmed),
-            _ => Err(ConversionError::ConfirmedBlock),
+            cert => Err(ConversionError::ConfirmedBlock { found: cert.kind() }),
         }
     }
 }
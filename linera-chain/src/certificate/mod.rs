@@ -75,6 +75,14 @@ impl Certificate {
         }
     }
 
+    pub fn kind(&self) -> CertificateKind {
+        match self {
+            Certificate::Validated(_) => CertificateKind::Validated,
+            Certificate::Confirmed(_) => CertificateKind::Confirmed,
+            Certificate::Timeout(_) => CertificateKind::Timeout,
+        }
+    }
+
     pub fn signatures(&self) -> &Vec<(ValidatorPublicKey, ValidatorSignature)> {
         match self {
             Certificate::Validated(cert) => cert.signatures(),
@@ -20,7 +20,7 @@ impl TryFrom<Certificate> for GenericCertificate<Timeout> {
     fn try_from(cert: Certificate) -> Result<Self, Self::Error> {
         match cert {
             Certificate::Timeout(timeout) => Ok(timeout),
-            _ => Err(ConversionError::Timeout),
+            cert => Err(ConversionError::Timeout { found: cert.kind() }),
         }
     }
 }
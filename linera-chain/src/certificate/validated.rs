@@ -37,7 +37,7 @@ impl TryFrom<Certificate> for GenericCertificate<ValidatedBlock> {
     fn try_from(cert: Certificate) -> Result<Self, Self::Error> {
         match cert {
             Certificate::Validated(validated) => Ok(validated),
-            _ => Err(ConversionError::ValidatedBlock),
+            cert => Err(ConversionError::ValidatedBlock { found: cert.kind() }),
         }
     }
 }
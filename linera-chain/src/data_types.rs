@@ -18,8 +18,8 @@ use linera_base::{
     data_types::{Amount, Blob, BlockHeight, Epoch, Event, OracleResponse, Round, Timestamp},
     doc_scalar, ensure, hex_debug,
     identifiers::{
-        Account, AccountOwner, BlobId, ChainId, ChannelFullName, Destination, GenericApplicationId,
-        MessageId,
+        Account, AccountOwner, ApplicationId, BlobId, ChainId, ChannelFullName, Destination,
+        GenericApplicationId, MessageId,
     },
 };
 use linera_execution::{
@@ -95,6 +95,24 @@ impl ProposedBlock {
                 .all(|message| message.action == MessageAction::Reject)
     }
 
+    /// Returns this block with the given incoming bundles, all of them rejected, and without
+    /// operations. The result satisfies [`ProposedBlock::has_only_rejected_messages`], so it
+    /// is admissible on closed chains, as long as none of the bundles is protected.
+    pub fn with_rejected_bundles_only(
+        mut self,
+        incoming_bundles: impl IntoIterator<Item = IncomingBundle>,
+    ) -> Self {
+        self.operations.clear();
+        self.incoming_bundles = incoming_bundles
+            .into_iter()
+            .map(|incoming_bundle| IncomingBundle {
+                action: MessageAction::Reject,
+                ..incoming_bundle
+            })
+            .collect();
+        self
+    }
+
     /// Returns an iterator over all incoming [`PostedMessage`]s in this block.
     pub fn incoming_messages(&self) -> impl Iterator<Item = &PostedMessage> {
         self.incoming_bundles
@@ -120,11 +138,104 @@ impl ProposedBlock {
         (0u32..).zip(bundles.chain(operations))
     }
 
+    /// Returns an upper bound on the size of this block's BCS serialization.
+    ///
+    /// User operations and messages, which make up the bulk of large blocks, are accounted
+    /// for by the length of their payload instead of being serialized.
+    pub fn estimate_size(&self) -> Result<usize, bcs::Error> {
+        // Destructure exhaustively, so that new fields can't be forgotten here.
+        let ProposedBlock {
+            chain_id,
+            epoch,
+            incoming_bundles,
+            operations,
+            height,
+            timestamp,
+            authenticated_signer,
+            previous_block_hash,
+        } = self;
+        let mut size = bcs::serialized_size(&(
+            chain_id,
+            epoch,
+            height,
+            timestamp,
+            authenticated_signer,
+            previous_block_hash,
+        ))?;
+        size += 2 * MAX_SEQUENCE_LENGTH_SIZE;
+        for IncomingBundle {
+            origin,
+            bundle,
+            action,
+        } in incoming_bundles
+        {
+            let MessageBundle {
+                height,
+                timestamp,
+                certificate_hash,
+                transaction_index,
+                messages,
+            } = bundle;
+            size += bcs::serialized_size(&(
+                origin,
+                height,
+                timestamp,
+                certificate_hash,
+                transaction_index,
+                action,
+            ))?;
+            size += MAX_SEQUENCE_LENGTH_SIZE;
+            for PostedMessage {
+                authenticated_signer,
+                grant,
+                refund_grant_to,
+                kind,
+                index,
+                message,
+            } in messages
+            {
+                size += bcs::serialized_size(&(
+                    authenticated_signer,
+                    grant,
+                    refund_grant_to,
+                    kind,
+                    index,
+                ))?;
+                size += match message {
+                    Message::User {
+                        application_id,
+                        bytes,
+                    } => estimate_user_payload_size(application_id, bytes)?,
+                    message @ Message::System(_) => bcs::serialized_size(message)?,
+                };
+            }
+        }
+        for operation in operations {
+            size += match operation {
+                Operation::User {
+                    application_id,
+                    bytes,
+                } => estimate_user_payload_size(application_id, bytes)?,
+                Operation::System(_) => bcs::serialized_size(operation)?,
+            };
+        }
+        Ok(size)
+    }
+
+    /// Checks that the serialized block does not exceed the given size. The exact size is
+    /// only computed if [`ProposedBlock::estimate_size`] exceeds the limit.
     pub fn check_proposal_size(&self, maximum_block_proposal_size: u64) -> Result<(), ChainError> {
+        let maximum_size = usize::try_from(maximum_block_proposal_size).unwrap_or(usize::MAX);
+        if self.estimate_size()? <= maximum_size {
+            return Ok(());
+        }
         let size = bcs::serialized_size(self)?;
         ensure!(
-            size <= usize::try_from(maximum_block_proposal_size).unwrap_or(usize::MAX),
-            ChainError::BlockProposalTooLarge
+            size <= maximum_size,
+            ChainError::BlockProposalTooLarge {
+                size: size as u64,
+                limit: maximum_block_proposal_size,
+            }
         );
         Ok(())
     }
@@ -139,8 +250,20 @@ impl ProposedBlock {
     }
 }
 
+/// The maximum size of a ULEB128-encoded sequence length in BCS, which is at most `2^31 - 1`.
+const MAX_SEQUENCE_LENGTH_SIZE: usize = 5;
+
+/// Returns an upper bound on the serialized size of a user operation or message.
+fn estimate_user_payload_size(
+    application_id: &ApplicationId,
+    bytes: &[u8],
+) -> Result<usize, bcs::Error> {
+    // The variant index, the application ID, the length of the payload and the payload.
+    Ok(1 + bcs::serialized_size(application_id)? + MAX_SEQUENCE_LENGTH_SIZE + bytes.len())
+}
+
 /// A transaction in a block: incoming messages or an operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Transaction<'a> {
     /// Receive a bundle of incoming messages.
     ReceiveMessages(&'a IncomingBundle),
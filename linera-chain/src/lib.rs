@@ -27,7 +27,7 @@ use linera_base::{
     bcs,
     crypto::{CryptoError, CryptoHash},
     data_types::{ArithmeticError, BlockHeight, Round, Timestamp},
-    identifiers::{ApplicationId, BlobId, ChainId},
+    identifiers::{AccountOwner, ApplicationId, BlobId, ChainId},
 };
 use linera_execution::ExecutionError;
 use linera_views::views::ViewError;
@@ -78,6 +78,19 @@ pub enum ChainError {
         next_height: BlockHeight,
         next_index: u32,
     },
+    #[error(
+        "Block proposed to {chain_id:?} has an incoming bundle from {origin:?} at height \
+        {height} and transaction index {index}, which does not come after the previous bundle \
+        from that origin at height {previous_height} and transaction index {previous_index}"
+    )]
+    IncomingBundleOutOfOrder {
+        chain_id: ChainId,
+        origin: Box<Origin>,
+        previous_height: BlockHeight,
+        previous_index: u32,
+        height: BlockHeight,
+        index: u32,
+    },
     #[error(
         "Block proposed to {chain_id:?} is attempting to reject protected message \
         {posted_message:?}"
@@ -116,6 +129,8 @@ pub enum ChainError {
     },
     #[error("The previous block hash of a new block should match the last block of the chain")]
     UnexpectedPreviousBlockHash,
+    #[error("Was expecting a block of chain {expected} but found chain {found} instead")]
+    UnexpectedChainId { expected: ChainId, found: ChainId },
     #[error("Sequence numbers above the maximal value are not usable for blocks")]
     InvalidBlockHeight,
     #[error("Block timestamp must not be earlier than the parent block's.")]
@@ -142,8 +157,8 @@ pub enum ChainError {
     CertificateSignatureVerificationFailed { error: String },
     #[error("Internal error {0}")]
     InternalError(String),
-    #[error("Block proposal is too large")]
-    BlockProposalTooLarge,
+    #[error("Block proposal has {size} bytes, which is more than the limit of {limit} bytes")]
+    BlockProposalTooLarge { size: u64, limit: u64 },
     #[error(transparent)]
     BcsError(#[from] bcs::Error),
     #[error("Insufficient balance to pay the fees")]
@@ -167,6 +182,19 @@ pub enum ChainError {
     },
     #[error("Blobs not found: {0:?}")]
     BlobsNotFound(Vec<BlobId>),
+    #[error("The block header's {field} does not match the block body")]
+    BlockHeaderHashMismatch { field: &'static str },
+    #[error("The block has operations but no authenticated signer")]
+    MissingAuthenticatedSigner,
+    #[error(
+        "Operation {index} acts on behalf of {owner} but the block's authenticated signer \
+        is {signer:?}"
+    )]
+    OperationSignerMismatch {
+        index: usize,
+        owner: AccountOwner,
+        signer: Option<AccountOwner>,
+    },
 }
 
 impl From<ViewError> for ChainError {
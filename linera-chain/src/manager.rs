@@ -504,7 +504,7 @@ where
         blobs: BTreeMap<BlobId, Blob>,
     ) -> Result<(), ViewError> {
         let round = validated.round;
-        let confirmed_block = ConfirmedBlock::new(validated.inner().block().clone());
+        let confirmed_block = ConfirmedBlock::from(validated.inner().clone());
         self.update_locking(LockingBlock::Regular(validated), blobs)?;
         self.update_current_round(local_time);
         if let Some(key_pair) = key_pair {
@@ -0,0 +1,1659 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use assert_matches::assert_matches;
+use linera_base::{
+    data_types::{Amount, Round},
+    identifiers::{ApplicationId, ChannelFullName, ModuleId, StreamId},
+    vm::VmRuntime,
+};
+use linera_execution::{system::Recipient, Message, MessageKind, SystemMessage, SystemOperation};
+
+use super::*;
+use crate::{
+    data_types::Origin,
+    test::{make_child_block, make_first_block, BlockTestExt, MessageTestExt},
+};
+
+/// Returns a block with one transfer operation per amount, where the `i`th operation sends
+/// `i` messages to chain 2.
+fn make_block(amounts: &[u128]) -> Block {
+    let mut proposed_block = make_first_block(ChainId::root(1));
+    let mut messages = Vec::new();
+    for (i, amount) in amounts.iter().enumerate() {
+        proposed_block =
+            proposed_block.with_simple_transfer(ChainId::root(2), Amount::from_tokens(*amount));
+        messages.push(
+            (0..i)
+                .map(|j| {
+                    let message = SystemMessage::Credit {
+                        target: AccountOwner::CHAIN,
+                        amount: Amount::from_tokens(j as u128),
+                        source: AccountOwner::CHAIN,
+                    };
+                    OutgoingMessage::new(ChainId::root(2), message)
+                })
+                .collect(),
+        );
+    }
+    BlockExecutionOutcome {
+        state_hash: CryptoHash::test_hash("state"),
+        oracle_responses: vec![Vec::new(); amounts.len()],
+        events: vec![Vec::new(); amounts.len()],
+        blobs: vec![Vec::new(); amounts.len()],
+        operation_results: vec![OperationResult::default(); amounts.len()],
+        messages,
+        ..BlockExecutionOutcome::default()
+    }
+    .with(proposed_block)
+}
+
+#[test]
+fn test_operation_merkle_proof() {
+    let block = make_block(&[1, 2, 3, 4]);
+    let operation = &block.body.operations[2];
+    let proof = block.merkle_proof_for_operation(2).unwrap();
+    assert!(block.header.verify_operation_proof(operation, &proof));
+    assert!(!block
+        .header
+        .verify_operation_proof(&block.body.operations[1], &proof));
+    assert!(block.merkle_proof_for_operation(4).is_none());
+
+    // Tampering with a sibling invalidates the proof.
+    let mut tampered = proof.clone();
+    tampered.levels[0].siblings[0] = CryptoHash::test_hash("tampered");
+    assert!(!block.header.verify_operation_proof(operation, &tampered));
+
+    // The proof does not verify against another block's header.
+    let other_block = make_block(&[1, 2, 5, 4]);
+    assert!(!other_block.header.verify_operation_proof(operation, &proof));
+}
+
+#[test]
+fn test_message_merkle_proof() {
+    let block = make_block(&[1, 2, 3]);
+    let message = &block.body.messages[2][1];
+    let proof = block.merkle_proof_for_message(2, 1).unwrap();
+    assert!(block.header.verify_message_proof(message, &proof));
+    assert!(!block
+        .header
+        .verify_message_proof(&block.body.messages[2][0], &proof));
+    assert!(block.merkle_proof_for_message(0, 0).is_none());
+    assert!(block.merkle_proof_for_message(3, 0).is_none());
+
+    let mut tampered = proof.clone();
+    tampered.levels[1].siblings[0] = CryptoHash::test_hash("tampered");
+    assert!(!block.header.verify_message_proof(message, &tampered));
+}
+
+#[test]
+fn test_required_event_ids() {
+    let chain_id = ChainId::root(1);
+    let stream_id = StreamId::system("stream");
+    let event = |index: u32| Event {
+        stream_id: stream_id.clone(),
+        index,
+        value: vec![index as u8],
+    };
+    let other_event_id = EventId {
+        chain_id: ChainId::root(2),
+        stream_id: stream_id.clone(),
+        index: 7,
+    };
+    let mut block = make_block(&[1, 2, 3]);
+    block.body.events = vec![vec![event(0), event(1)], Vec::new(), vec![event(1)]];
+    block.body.oracle_responses = vec![
+        vec![OracleResponse::Event(other_event_id.clone(), Vec::new())],
+        vec![OracleResponse::Round(None)],
+        vec![OracleResponse::Event(other_event_id.clone(), Vec::new())],
+    ];
+
+    let expected = HashSet::from([event(0).id(chain_id), event(1).id(chain_id), other_event_id]);
+    assert_eq!(block.required_event_ids(), expected);
+    assert!(make_block(&[1, 2]).required_event_ids().is_empty());
+}
+
+#[test]
+fn test_reverify_hash() {
+    let block = make_block(&[1, 2]);
+    let confirmed = ConfirmedBlock::new(block.clone());
+    confirmed.reverify_hash().unwrap();
+
+    let wrong_hash = CryptoHash::test_hash("wrong");
+    let corrupted = ConfirmedBlock::from_hashed(Hashed::unchecked_new(block, wrong_hash));
+    assert_matches!(
+        corrupted.reverify_hash(),
+        Err(ChainError::CertificateValueHashMismatch { expected, actual })
+            if expected == wrong_hash && actual == confirmed.inner().hash()
+    );
+}
+
+#[cfg(with_rayon)]
+#[test]
+fn test_verify_blocks_parallel() {
+    let mut blocks = (1..=20)
+        .map(|count| {
+            let block = make_block(&vec![1; count]);
+            (CryptoHash::new(&block), block)
+        })
+        .collect::<Vec<_>>();
+    verify_blocks_parallel(&blocks).unwrap();
+    verify_blocks_parallel(&[]).unwrap();
+
+    let wrong_hash = CryptoHash::test_hash("wrong");
+    blocks[13].0 = wrong_hash;
+    let actual = CryptoHash::new(&blocks[13].1);
+    assert_matches!(
+        verify_blocks_parallel(&blocks),
+        Err(ChainError::CertificateValueHashMismatch { expected, actual: found })
+            if expected == wrong_hash && found == actual
+    );
+}
+
+#[test]
+fn test_prefix() {
+    let (proposed_block, mut outcome) = make_block(&[1, 2, 3, 4]).into_proposal();
+    outcome
+        .previous_message_blocks
+        .insert(ChainId::root(2), CryptoHash::test_hash("previous"));
+    let block = Block::new(proposed_block, outcome);
+
+    let prefix = block.prefix(2);
+    prefix.validate_hashes().unwrap();
+    assert_eq!(prefix.body.operations, block.body.operations[..2]);
+    assert_eq!(prefix.body.messages, block.body.messages[..2]);
+    assert_eq!(prefix.body.operation_results.len(), 2);
+    assert_eq!(prefix.body.previous_message_blocks.len(), 1);
+    assert_eq!(prefix.header.state_hash, block.header.state_hash);
+    assert_ne!(prefix.header.operations_hash, block.header.operations_hash);
+
+    // The first operation sends no messages, so the previous message block is dropped.
+    let prefix = block.prefix(1);
+    prefix.validate_hashes().unwrap();
+    assert_eq!(prefix.body.operations.len(), 1);
+    assert!(prefix.body.previous_message_blocks.is_empty());
+
+    assert_eq!(block.prefix(4), block);
+    assert_eq!(block.prefix(10), block);
+}
+
+#[test]
+fn test_empty_body_hashes() {
+    let empty = BlockBody::empty();
+    assert!(empty.is_canonically_empty());
+    assert_eq!(*EMPTY_BODY_HASHES, empty.compute_hashes());
+    assert_eq!(
+        EMPTY_BODY_HASHES.operations_hash,
+        CryptoHash::new(&CryptoHashVec(Vec::new()))
+    );
+
+    let block = BlockExecutionOutcome::default().with(make_first_block(ChainId::root(1)));
+    assert_eq!(block.body, empty);
+    assert_eq!(
+        block.header.operations_hash,
+        EMPTY_BODY_HASHES.operations_hash
+    );
+    block.validate_hashes().unwrap();
+
+    let block = make_block(&[1]);
+    assert!(!block.body.is_canonically_empty());
+    assert_ne!(block.body.hashes(), *EMPTY_BODY_HASHES);
+    block.validate_hashes().unwrap();
+}
+
+#[test]
+fn test_follows() {
+    let parent = BlockExecutionOutcome::default()
+        .with(make_first_block(ChainId::root(1)).with_timestamp(1000));
+    let parent = ConfirmedBlock::new(parent);
+    let child = |f: fn(&mut ProposedBlock)| {
+        let mut proposed_block = make_child_block(&parent);
+        f(&mut proposed_block);
+        BlockExecutionOutcome::default().with(proposed_block)
+    };
+    assert!(child(|_| {}).follows(parent.inner()).is_ok());
+    assert!(child(|block| block.timestamp = 1001.into())
+        .follows(parent.inner())
+        .is_ok());
+
+    assert_matches!(
+        child(|block| block.chain_id = ChainId::root(2)).follows(parent.inner()),
+        Err(ChainError::UnexpectedChainId { expected, found })
+            if expected == ChainId::root(1) && found == ChainId::root(2)
+    );
+    assert_matches!(
+        child(|block| block.height = BlockHeight(2)).follows(parent.inner()),
+        Err(ChainError::UnexpectedBlockHeight {
+            expected_block_height: BlockHeight(1),
+            found_block_height: BlockHeight(2),
+        })
+    );
+    assert_matches!(
+        child(|block| block.previous_block_hash = None).follows(parent.inner()),
+        Err(ChainError::UnexpectedPreviousBlockHash)
+    );
+    assert_matches!(
+        child(|block| block.previous_block_hash = Some(CryptoHash::test_hash("other")))
+            .follows(parent.inner()),
+        Err(ChainError::UnexpectedPreviousBlockHash)
+    );
+    assert_matches!(
+        child(|block| block.timestamp = 999.into()).follows(parent.inner()),
+        Err(ChainError::InvalidBlockTimestamp)
+    );
+}
+
+#[test]
+fn test_header_with_commitment() {
+    let block_a = make_block(&[1, 2]);
+    let block_b = make_block(&[1, 3]);
+    let (header, hash) = block_a.header_with_commitment();
+    assert_eq!(hash, ConfirmedBlock::new(block_a.clone()).inner().hash());
+    assert!(header.verify_against(&block_a));
+    assert!(!header.verify_against(&block_b));
+    assert_ne!(hash, block_b.header_with_commitment().1);
+
+    // A header with the right body hashes but other metadata doesn't verify either.
+    let mut later_block = block_a.clone();
+    later_block.header.height = BlockHeight(1);
+    assert!(!header.verify_against(&later_block));
+
+    // Neither does the right header with a tampered body.
+    let mut tampered = block_a;
+    tampered.body.messages[1].clear();
+    assert!(!header.verify_against(&tampered));
+}
+
+#[test]
+fn test_canonical_bytes() {
+    let block = make_block(&[1, 2, 3]);
+    let bytes = block.to_canonical_bytes();
+    assert_eq!(bytes[..5], *b"LNBK\x01");
+    assert_eq!(bytes[5..], bcs::to_bytes(&block).unwrap());
+    assert_eq!(Block::from_canonical_bytes(&bytes).unwrap(), block);
+
+    let mut unknown_version = bytes.clone();
+    unknown_version[4] = 2;
+    assert_matches!(
+        Block::from_canonical_bytes(&unknown_version),
+        Err(BlockDecodingError::UnknownVersion(2))
+    );
+    assert_matches!(
+        Block::from_canonical_bytes(&bytes[5..]),
+        Err(BlockDecodingError::MissingMagic)
+    );
+    assert_matches!(
+        Block::from_canonical_bytes(&bytes[..4]),
+        Err(BlockDecodingError::MissingMagic)
+    );
+    assert_matches!(
+        Block::from_canonical_bytes(&bytes[..bytes.len() - 1]),
+        Err(BlockDecodingError::Bcs(_))
+    );
+}
+
+#[test]
+fn test_flattened_events() {
+    let stream_id = StreamId::system("stream");
+    let event = |index: u32| Event {
+        stream_id: stream_id.clone(),
+        index,
+        value: vec![index as u8],
+    };
+    let mut block = make_block(&[1, 2, 3, 4]);
+    block.body.events = vec![
+        vec![event(0), event(1)],
+        Vec::new(),
+        vec![event(2)],
+        vec![event(3), event(4)],
+    ];
+
+    let events = block.flattened_events();
+    let indices = events.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+    let expected = [(0, 0), (0, 1), (2, 0), (3, 0), (3, 1)];
+    for ((_, event), (transaction_index, index)) in events.into_iter().zip(expected) {
+        assert_eq!(event, &block.body.events[transaction_index][index]);
+    }
+    assert!(make_block(&[1, 2]).flattened_events().is_empty());
+}
+
+#[test]
+fn test_try_message_id_for_operation() {
+    let block = make_block(&[1, 2, 3]);
+    assert_eq!(
+        block.try_message_id_for_operation(2, 1),
+        Ok(block.message_id(2))
+    );
+    assert_eq!(
+        block.try_message_id_for_operation(3, 0),
+        Err(MessageIdError::OperationOutOfRange {
+            requested: 3,
+            available: 3,
+        })
+    );
+    assert_eq!(
+        block.try_message_id_for_operation(1, 1),
+        Err(MessageIdError::MessageOutOfRange {
+            requested: 1,
+            available: 1,
+        })
+    );
+    assert_eq!(
+        block.try_message_id_for_operation(0, 0),
+        Err(MessageIdError::MessageOutOfRange {
+            requested: 0,
+            available: 0,
+        })
+    );
+    assert_eq!(block.message_id_for_operation(0, 0), None);
+    // `IndexOverflow` requires more than `u32::MAX` messages, too many to test.
+}
+
+#[test]
+fn test_oracle_responses_for() {
+    let mut block = make_block(&[1, 2]);
+    let blob_id = BlobId::new(CryptoHash::test_hash("blob"), BlobType::Data);
+    let responses = vec![OracleResponse::Blob(blob_id), OracleResponse::Assert];
+    block.body.oracle_responses[1] = responses.clone();
+    assert_eq!(block.oracle_responses_for(0), Some(&[][..]));
+    assert_eq!(block.oracle_responses_for(1), Some(&responses[..]));
+    assert_eq!(block.oracle_responses_for(2), None);
+}
+
+#[test]
+fn test_checked_message_count() {
+    assert_eq!(make_block(&[1, 2, 3]).checked_message_count(), Some(3));
+    assert_eq!(make_block(&[]).checked_message_count(), Some(0));
+
+    // Blocks with more than `u32::MAX` messages don't fit in memory, so test the sum alone.
+    let max = u32::MAX as usize;
+    assert_eq!(checked_u32_sum(vec![1; 1000]), Some(1000));
+    assert_eq!(checked_u32_sum([max - 2, 1, 1]), Some(u32::MAX));
+    assert_eq!(checked_u32_sum([max - 2, 1, 1, 1]), None);
+    assert_eq!(checked_u32_sum([max, 0, 1]), None);
+    assert_eq!(checked_u32_sum([max + 1]), None);
+}
+
+#[test]
+fn test_outgoing_messages_with_ids() {
+    let block = make_block(&[1, 2, 3, 4]);
+    let mut expected = Vec::new();
+    for (operation_index, messages) in block.messages().iter().enumerate() {
+        for (message_index, message) in (0..).zip(messages) {
+            let message_id = block
+                .message_id_for_operation(operation_index, message_index)
+                .unwrap();
+            expected.push((message_id, message));
+        }
+    }
+    let actual = block.outgoing_messages_with_ids().collect::<Vec<_>>();
+    assert_eq!(actual.len(), 6);
+    assert_eq!(actual, expected);
+    for (message_id, message) in actual {
+        assert_eq!(block.message_by_id(&message_id), Some(message));
+    }
+    let missing_id = block.message_id(6);
+    assert_eq!(block.message_by_id(&missing_id), None);
+}
+
+#[test]
+fn test_into_flat_messages() {
+    let block = make_block(&[1, 2, 3, 4]);
+    let expected = block
+        .outgoing_messages_with_ids()
+        .map(|(message_id, message)| (message_id, message.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(expected.len(), 6);
+    assert_eq!(block.into_flat_messages(), expected);
+    assert!(make_block(&[]).into_flat_messages().is_empty());
+}
+
+#[test]
+fn test_messages_by_recipient() {
+    let (proposed_block, mut outcome) = make_block(&[1, 2, 3]).into_proposal();
+    let message = SystemMessage::Credit {
+        target: AccountOwner::CHAIN,
+        amount: Amount::ONE,
+        source: AccountOwner::CHAIN,
+    };
+    outcome.messages[1].push(OutgoingMessage::new(ChainId::root(3), message.clone()));
+    outcome.messages[2].push(OutgoingMessage {
+        destination: Destination::Subscribers(vec![1].into()),
+        ..OutgoingMessage::new(ChainId::root(3), message)
+    });
+    let block = Block::new(proposed_block, outcome);
+
+    let grouped = block.messages_by_recipient();
+    assert_eq!(grouped.len(), 2);
+    let to_chain_2 = &grouped[&ChainId::root(2)];
+    assert_eq!(to_chain_2.len(), 3);
+    assert_eq!(
+        to_chain_2
+            .iter()
+            .map(|(id, _)| id.index)
+            .collect::<Vec<_>>(),
+        vec![0, 2, 3]
+    );
+    let to_chain_3 = &grouped[&ChainId::root(3)];
+    assert_eq!(to_chain_3.len(), 1);
+    assert_eq!(to_chain_3[0].0, block.message_id(1));
+    for (message_id, message) in grouped.values().flatten() {
+        assert_eq!(block.message_by_id(message_id), Some(*message));
+    }
+}
+
+#[test]
+fn test_all_message_ids() {
+    let block = make_block(&[1, 2, 3, 4]);
+    let mut expected = Vec::new();
+    for (operation_index, messages) in block.messages().iter().enumerate() {
+        for message_index in 0..messages.len() as u32 {
+            expected.push(
+                block
+                    .message_id_for_operation(operation_index, message_index)
+                    .unwrap(),
+            );
+        }
+    }
+    let message_ids = block.all_message_ids();
+    assert_eq!(message_ids.len(), 6);
+    assert_eq!(message_ids, expected);
+    assert!(make_block(&[1]).all_message_ids().is_empty());
+}
+
+#[tokio::test]
+async fn test_block_graphql_fields() {
+    use async_graphql::{
+        EmptyMutation, EmptySubscription, Name, Object, ScalarType, Schema, Value,
+    };
+
+    struct Query(Block);
+
+    #[Object]
+    impl Query {
+        async fn block(&self) -> &Block {
+            &self.0
+        }
+    }
+
+    let blob_id = |name| BlobId::new(CryptoHash::test_hash(name), BlobType::Data);
+    let mut block = make_block(&[1, 2, 3]);
+    block.body.oracle_responses[2] = vec![
+        OracleResponse::Blob(blob_id("blob2")),
+        OracleResponse::Blob(blob_id("blob1")),
+    ];
+    let schema = Schema::new(Query(block.clone()), EmptyMutation, EmptySubscription);
+    let response = schema
+        .execute("{ block { flattenedMessages { messageId } requiredBlobIds } }")
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let Value::Object(data) = response.data else {
+        panic!("unexpected response: {:?}", response.data);
+    };
+    let Some(Value::Object(block_data)) = data.get("block") else {
+        panic!("missing block in response: {data:?}");
+    };
+    let expected_messages = block
+        .all_message_ids()
+        .iter()
+        .map(|message_id| {
+            Value::Object(
+                [(Name::new("messageId"), message_id.to_value())]
+                    .into_iter()
+                    .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expected_messages.len(), 3);
+    assert_eq!(
+        block_data["flattenedMessages"],
+        Value::List(expected_messages)
+    );
+    let expected_blob_ids = block
+        .required_blob_ids()
+        .iter()
+        .map(ScalarType::to_value)
+        .collect::<Vec<_>>();
+    assert_eq!(expected_blob_ids.len(), 2);
+    assert_eq!(
+        block_data["requiredBlobIds"],
+        Value::List(expected_blob_ids)
+    );
+}
+
+#[test]
+fn test_validate_hashes() {
+    let mut block = make_block(&[1, 2, 3]);
+    block.body.events[1].push(Event {
+        stream_id: StreamId::system("stream"),
+        index: 0,
+        value: vec![1],
+    });
+    let (proposed_block, outcome) = block.into_proposal();
+    let block = Block::new(proposed_block, outcome);
+    assert!(block.validate_hashes().is_ok());
+
+    let assert_mismatch = |expected_field: &str, corrupt: fn(&mut BlockBody)| {
+        let mut corrupted = block.clone();
+        corrupt(&mut corrupted.body);
+        assert_matches!(
+            corrupted.validate_hashes(),
+            Err(ChainError::BlockHeaderHashMismatch { field }) if field == expected_field
+        );
+    };
+    assert_mismatch("bundles_hash", |body| {
+        body.incoming_bundles.push(IncomingBundle {
+            origin: Origin::chain(ChainId::root(2)),
+            bundle: MessageBundle {
+                height: BlockHeight(0),
+                timestamp: Timestamp::from(0),
+                certificate_hash: CryptoHash::test_hash("certificate"),
+                transaction_index: 0,
+                messages: Vec::new(),
+            },
+            action: MessageAction::Reject,
+        })
+    });
+    assert_mismatch("operations_hash", |body| {
+        body.operations.pop();
+    });
+    assert_mismatch("messages_hash", |body| {
+        body.messages[2].pop();
+    });
+    assert_mismatch("previous_message_blocks_hash", |body| {
+        body.previous_message_blocks
+            .insert(ChainId::root(2), CryptoHash::test_hash("block"));
+    });
+    assert_mismatch("oracle_responses_hash", |body| {
+        body.oracle_responses[0].push(OracleResponse::Assert)
+    });
+    assert_mismatch("events_hash", |body| body.events[1].clear());
+    assert_mismatch("blobs_hash", |body| body.blobs.push(Vec::new()));
+    assert_mismatch("operation_results_hash", |body| {
+        body.operation_results[0] = OperationResult(vec![1]);
+    });
+}
+
+#[test]
+fn test_redact_oracle_responses() {
+    let (proposed_block, mut outcome) = make_block(&[1, 2]).into_proposal();
+    outcome.oracle_responses = vec![
+        vec![OracleResponse::Assert],
+        vec![OracleResponse::Round(Some(2))],
+    ];
+    let block = Block::new(proposed_block, outcome);
+    let block_hash = Hashed::new(block.clone()).hash();
+
+    let redacted = block.clone().redact_oracle_responses();
+    assert_eq!(redacted.header, block.header);
+    assert!(redacted.body.oracle_responses.is_empty());
+    assert!(redacted.verify_header().is_ok());
+
+    // The other fields of the body are still checked.
+    let mut tampered = redacted.clone();
+    tampered.body.messages[1].pop();
+    assert_matches!(
+        tampered.verify_header(),
+        Err(ChainError::BlockHeaderHashMismatch {
+            field: "messages_hash"
+        })
+    );
+
+    // The original block can only be restored with the original oracle responses.
+    assert_matches!(
+        redacted
+            .clone()
+            .with_oracle_responses(vec![Vec::new(), Vec::new()]),
+        Err(ChainError::BlockHeaderHashMismatch {
+            field: "oracle_responses_hash"
+        })
+    );
+    let restored = redacted
+        .with_oracle_responses(block.body.oracle_responses.clone())
+        .unwrap();
+    assert_eq!(Hashed::new(restored).hash(), block_hash);
+}
+
+#[test]
+fn test_block_diff() {
+    let block = make_block(&[1, 2, 3]);
+    assert!(block.diff(&block).is_empty());
+
+    // Changing an operation changes the operations hash and that transaction only.
+    let (mut proposed_block, outcome) = block.clone().into_proposal();
+    proposed_block.operations[1] = SystemOperation::Transfer {
+        owner: AccountOwner::CHAIN,
+        recipient: Recipient::chain(ChainId::root(3)),
+        amount: Amount::ONE,
+    }
+    .into();
+    let other = Block::new(proposed_block, outcome);
+    let diff = block.diff(&other);
+    assert_eq!(diff.header_fields, ["operations_hash"]);
+    assert_eq!(diff.transactions, [1]);
+    assert!(!diff.previous_message_blocks);
+    assert_eq!(other.diff(&block), diff);
+
+    // Differences in the outcome are reported, too.
+    let (proposed_block, mut outcome) = block.clone().into_proposal();
+    outcome.state_hash = CryptoHash::test_hash("other state");
+    outcome.messages[2].pop();
+    outcome.oracle_responses[0].push(OracleResponse::Assert);
+    let other = Block::new(proposed_block, outcome);
+    let diff = block.diff(&other);
+    assert_eq!(
+        diff.header_fields,
+        ["state_hash", "messages_hash", "oracle_responses_hash"]
+    );
+    assert_eq!(diff.transactions, [0, 2]);
+
+    // Extra transactions are reported.
+    let other = make_block(&[1, 2, 3, 4]);
+    assert_eq!(block.diff(&other).transactions, [3]);
+}
+
+#[test]
+fn test_operation_count_by_application() {
+    let application1 = ApplicationId::new(CryptoHash::test_hash("application1"));
+    let application2 = ApplicationId::new(CryptoHash::test_hash("application2"));
+    let user_operation = |application_id| Operation::User {
+        application_id,
+        bytes: vec![1, 2, 3],
+    };
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_operation(user_operation(application1))
+        .with_operation(user_operation(application2))
+        .with_simple_transfer(ChainId::root(3), Amount::ONE)
+        .with_operation(user_operation(application1))
+        .with_operation(user_operation(application1));
+    let block = BlockExecutionOutcome::default().with(proposed_block);
+
+    let expected = BTreeMap::from([
+        (GenericApplicationId::System, 2),
+        (GenericApplicationId::User(application1), 3),
+        (GenericApplicationId::User(application2), 1),
+    ]);
+    assert_eq!(block.body.operation_count_by_application(), expected);
+    assert!(BlockExecutionOutcome::default()
+        .with(make_first_block(ChainId::root(1)))
+        .body
+        .operation_count_by_application()
+        .is_empty());
+}
+
+#[test]
+fn test_find_operation() {
+    let application1 = ApplicationId::new(CryptoHash::test_hash("application1"));
+    let application2 = ApplicationId::new(CryptoHash::test_hash("application2"));
+    let user_operation = |application_id| Operation::User {
+        application_id,
+        bytes: vec![1, 2, 3],
+    };
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_operation(user_operation(application1))
+        .with_simple_transfer(ChainId::root(3), Amount::from_tokens(5))
+        .with_operation(user_operation(application1));
+    let body = BlockExecutionOutcome::default().with(proposed_block).body;
+
+    let is_large_transfer = |operation: &Operation| {
+        matches!(
+            operation,
+            Operation::System(operation) if matches!(
+                **operation,
+                SystemOperation::Transfer { amount, .. } if amount > Amount::ONE
+            )
+        )
+    };
+    assert!(body.any_operation(is_large_transfer));
+    assert_eq!(
+        body.find_operation(is_large_transfer),
+        Some((2, &body.operations[2]))
+    );
+    let is_user_operation = |operation: &Operation| matches!(operation, Operation::User { .. });
+    assert_eq!(
+        body.find_operation(is_user_operation),
+        Some((1, &body.operations[1]))
+    );
+
+    let is_application2 = |operation: &Operation| operation.application_id() == application2.into();
+    assert!(!body.any_operation(is_application2));
+    assert_eq!(body.find_operation(is_application2), None);
+}
+
+#[test]
+fn test_gas_weight_hint() {
+    let block = make_block(&[1, 2, 3]);
+    assert!(block.gas_weight_hint() > make_block(&[1, 2]).gas_weight_hint());
+    assert_eq!(
+        BlockExecutionOutcome::default()
+            .with(make_first_block(ChainId::root(1)))
+            .gas_weight_hint(),
+        0
+    );
+
+    let mut with_oracle_responses = block.clone();
+    with_oracle_responses.body.oracle_responses[1].push(OracleResponse::Round(None));
+    assert!(with_oracle_responses.gas_weight_hint() > block.gas_weight_hint());
+
+    let mut with_blob = block.clone();
+    with_blob.body.blobs[0].push(Blob::new_data(b"data".to_vec()));
+    assert!(with_blob.gas_weight_hint() > block.gas_weight_hint());
+}
+
+#[test]
+fn test_fee_relevant_summary() {
+    let posted_message = |index| {
+        SystemMessage::Credit {
+            target: AccountOwner::CHAIN,
+            amount: Amount::ONE,
+            source: AccountOwner::CHAIN,
+        }
+        .to_posted(index, MessageKind::Tracked)
+    };
+    let publish_blob = |name| SystemOperation::PublishDataBlob {
+        blob_hash: CryptoHash::test_hash(name),
+    };
+    // One incoming bundle and three operations, two of which publish the same blob.
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_incoming_bundle(IncomingBundle {
+            origin: Origin::chain(ChainId::root(2)),
+            bundle: MessageBundle {
+                height: BlockHeight(0),
+                timestamp: Timestamp::from(0),
+                certificate_hash: CryptoHash::test_hash("certificate"),
+                transaction_index: 0,
+                messages: vec![posted_message(0), posted_message(1)],
+            },
+            action: MessageAction::Accept,
+        })
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_operation(publish_blob("blob"))
+        .with_operation(publish_blob("blob"));
+    let credit = SystemMessage::Credit {
+        target: AccountOwner::CHAIN,
+        amount: Amount::ONE,
+        source: AccountOwner::CHAIN,
+    };
+    let outcome = BlockExecutionOutcome {
+        state_hash: CryptoHash::test_hash("state"),
+        messages: vec![
+            Vec::new(),
+            vec![
+                OutgoingMessage::new(ChainId::root(2), credit.clone()),
+                OutgoingMessage::new(ChainId::root(3), credit),
+            ],
+            Vec::new(),
+            Vec::new(),
+        ],
+        oracle_responses: vec![
+            vec![OracleResponse::Assert],
+            Vec::new(),
+            vec![OracleResponse::Round(None)],
+            Vec::new(),
+        ],
+        events: vec![Vec::new(); 4],
+        blobs: vec![Vec::new(); 4],
+        operation_results: vec![OperationResult::default(); 3],
+        ..BlockExecutionOutcome::default()
+    };
+    let block = Block::new(proposed_block.clone(), outcome);
+
+    let summary = block.fee_relevant_summary().unwrap();
+    assert_eq!(summary.operations, 3);
+    assert_eq!(summary.incoming_messages, proposed_block.message_count());
+    assert_eq!(summary.incoming_messages, 2);
+    assert_eq!(
+        summary.outgoing_messages,
+        block.outgoing_messages_with_ids().count()
+    );
+    assert_eq!(summary.outgoing_messages, 2);
+    assert_eq!(summary.oracle_responses, block.oracle_response_count());
+    assert_eq!(summary.oracle_responses, 2);
+    assert_eq!(summary.published_blobs, block.published_blob_ids().len());
+    assert_eq!(summary.published_blobs, 1);
+    assert_eq!(
+        summary.body_size,
+        bcs::serialized_size(&block.body).unwrap()
+    );
+}
+
+#[test]
+fn test_author() {
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let other_owner = AccountOwner::from(CryptoHash::test_hash("other owner"));
+    let proposed_block = make_first_block(ChainId::root(1)).with_authenticated_signer(Some(owner));
+    let block = BlockExecutionOutcome::default().with(proposed_block);
+    assert_eq!(block.author(), Some(owner));
+    assert!(block.is_authored_by(&owner));
+    assert!(!block.is_authored_by(&other_owner));
+
+    let block = BlockExecutionOutcome::default().with(make_first_block(ChainId::root(1)));
+    assert_eq!(block.author(), None);
+    assert!(!block.is_authored_by(&owner));
+}
+
+#[test]
+fn test_check_authentication() {
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let empty_block = BlockExecutionOutcome::default().with(make_first_block(ChainId::root(1)));
+    assert!(!empty_block.requires_authenticated_signer());
+    empty_block.check_authentication().unwrap();
+
+    let block = make_block(&[1, 2]);
+    assert!(block.requires_authenticated_signer());
+    assert_matches!(
+        block.check_authentication(),
+        Err(ChainError::MissingAuthenticatedSigner)
+    );
+
+    let (proposed_block, outcome) = block.into_proposal();
+    let block = outcome.with(proposed_block.with_authenticated_signer(Some(owner)));
+    block.check_authentication().unwrap();
+}
+
+#[test]
+fn test_check_signer_consistency() {
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let other_owner = AccountOwner::from(CryptoHash::test_hash("other owner"));
+    let recipient = Recipient::chain(ChainId::root(2));
+    let amount = Amount::ONE;
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_authenticated_signer(Some(owner))
+        .with_simple_transfer(ChainId::root(2), amount)
+        .with_transfer(owner, recipient, amount)
+        .with_operation(SystemOperation::Claim {
+            owner,
+            target_id: ChainId::root(3),
+            recipient,
+            amount,
+        });
+    let block = BlockExecutionOutcome::default().with(proposed_block.clone());
+    block.check_signer_consistency().unwrap();
+
+    let block = BlockExecutionOutcome::default().with(proposed_block.with_transfer(
+        other_owner,
+        recipient,
+        amount,
+    ));
+    assert_matches!(
+        block.check_signer_consistency(),
+        Err(ChainError::OperationSignerMismatch {
+            index: 3,
+            owner: mismatched_owner,
+            signer: Some(signer),
+        }) if mismatched_owner == other_owner && signer == owner
+    );
+
+    let block = BlockExecutionOutcome::default().with(
+        make_first_block(ChainId::root(1)).with_operation(SystemOperation::Claim {
+            owner,
+            target_id: ChainId::root(3),
+            recipient,
+            amount,
+        }),
+    );
+    assert_matches!(
+        block.check_signer_consistency(),
+        Err(ChainError::OperationSignerMismatch {
+            index: 0,
+            signer: None,
+            ..
+        })
+    );
+}
+
+#[test]
+fn test_oracle_response_counts() {
+    let blob_id = |name| BlobId::new(CryptoHash::test_hash(name), BlobType::Data);
+    let mut block = make_block(&[1, 2, 3]);
+    block.body.oracle_responses = vec![
+        vec![
+            OracleResponse::Blob(blob_id("blob1")),
+            OracleResponse::Assert,
+            OracleResponse::Blob(blob_id("blob2")),
+        ],
+        Vec::new(),
+        vec![
+            OracleResponse::Round(Some(1)),
+            OracleResponse::Blob(blob_id("blob1")),
+            OracleResponse::Service(vec![1]),
+        ],
+    ];
+    assert_eq!(block.oracle_response_count(), 6);
+    assert_eq!(
+        block.oracle_response_counts(),
+        BTreeMap::from([
+            (OracleResponseKind::Service, 1),
+            (OracleResponseKind::Blob, 3),
+            (OracleResponseKind::Assert, 1),
+            (OracleResponseKind::Round, 1),
+        ])
+    );
+    let block = make_block(&[1, 2]);
+    assert_eq!(block.oracle_response_count(), 0);
+    assert!(block.oracle_response_counts().is_empty());
+}
+
+#[test]
+fn test_is_empty_and_has_side_effects() {
+    let chain_id = ChainId::root(1);
+
+    // No transactions and no side effects.
+    let block = BlockExecutionOutcome::default().with(make_first_block(chain_id));
+    assert!(block.is_empty());
+    assert!(!block.has_side_effects());
+
+    // Transactions without side effects.
+    let block = make_block(&[1]);
+    assert!(!block.is_empty());
+    assert!(!block.has_side_effects());
+
+    // Transactions with outgoing messages, events or oracle responses.
+    let block = make_block(&[1, 2]);
+    assert!(!block.is_empty());
+    assert!(block.has_side_effects());
+    let mut block = make_block(&[1]);
+    block.body.events[0].push(Event {
+        stream_id: StreamId::system("stream"),
+        index: 0,
+        value: Vec::new(),
+    });
+    assert!(block.has_side_effects());
+    let mut block = make_block(&[1]);
+    block.body.oracle_responses[0].push(OracleResponse::Assert);
+    assert!(block.has_side_effects());
+
+    // Side effects without transactions.
+    let mut block = BlockExecutionOutcome::default().with(make_first_block(chain_id));
+    block.body.events.push(Vec::new());
+    block
+        .body
+        .oracle_responses
+        .push(vec![OracleResponse::Assert]);
+    assert!(block.is_empty());
+    assert!(block.has_side_effects());
+}
+
+#[test]
+fn test_incoming_bundle_senders() {
+    let bundle = |sender: u32, height: u64| IncomingBundle {
+        origin: Origin::chain(ChainId::root(sender)),
+        bundle: MessageBundle {
+            height: BlockHeight(height),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index: 0,
+            messages: Vec::new(),
+        },
+        action: MessageAction::Accept,
+    };
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_incoming_bundle(bundle(2, 0))
+        .with_incoming_bundle(bundle(3, 0))
+        .with_incoming_bundle(bundle(2, 1));
+    let block = BlockExecutionOutcome::default().with(proposed_block);
+    assert_eq!(
+        block.incoming_bundle_senders(),
+        BTreeSet::from([ChainId::root(2), ChainId::root(3)])
+    );
+    let block = BlockExecutionOutcome::default().with(make_first_block(ChainId::root(1)));
+    assert!(block.incoming_bundle_senders().is_empty());
+}
+
+#[test]
+fn test_transaction_at() {
+    let bundle = |height: u64| IncomingBundle {
+        origin: Origin::chain(ChainId::root(2)),
+        bundle: MessageBundle {
+            height: BlockHeight(height),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index: 0,
+            messages: Vec::new(),
+        },
+        action: MessageAction::Accept,
+    };
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_incoming_bundle(bundle(0))
+        .with_incoming_bundle(bundle(1))
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_simple_transfer(ChainId::root(3), Amount::ONE);
+    let block = BlockExecutionOutcome::default().with(proposed_block.clone());
+
+    let transactions = block.transactions().collect::<Vec<_>>();
+    assert_eq!(transactions.len(), 4);
+    assert_eq!(
+        transactions,
+        proposed_block.transactions().collect::<Vec<_>>()
+    );
+    for (index, transaction) in transactions {
+        assert_eq!(block.transaction_at(index), Some(transaction));
+    }
+    assert_matches!(
+        block.transaction_at(1),
+        Some(Transaction::ReceiveMessages(_))
+    );
+    assert_matches!(
+        block.transaction_at(2),
+        Some(Transaction::ExecuteOperation(_))
+    );
+    assert_eq!(block.transaction_at(4), None);
+    assert_eq!(block.transaction_at(u32::MAX), None);
+}
+
+#[test]
+fn test_incoming_bundle() {
+    let bundle = |sender: u32, height: u64, transaction_index: u32| IncomingBundle {
+        origin: Origin::chain(ChainId::root(sender)),
+        bundle: MessageBundle {
+            height: BlockHeight(height),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index,
+            messages: Vec::new(),
+        },
+        action: MessageAction::Accept,
+    };
+    let proposed_block = make_first_block(ChainId::root(1))
+        .with_incoming_bundle(bundle(2, 0, 0))
+        .with_incoming_bundle(bundle(3, 0, 0))
+        .with_incoming_bundle(bundle(2, 1, 0))
+        .with_incoming_bundle(bundle(2, 1, 1));
+    let block = BlockExecutionOutcome::default().with(proposed_block);
+
+    let origin = |sender| Origin::chain(ChainId::root(sender));
+    assert_eq!(
+        block.incoming_bundle(&origin(2), BlockHeight(0)),
+        Some(&bundle(2, 0, 0))
+    );
+    assert_eq!(
+        block.incoming_bundle(&origin(3), BlockHeight(0)),
+        Some(&bundle(3, 0, 0))
+    );
+    assert_eq!(
+        block.incoming_bundle(&origin(2), BlockHeight(1)),
+        Some(&bundle(2, 1, 0))
+    );
+    assert_eq!(block.incoming_bundle(&origin(3), BlockHeight(1)), None);
+    assert_eq!(block.incoming_bundle(&origin(4), BlockHeight(0)), None);
+}
+
+#[test]
+fn test_validate_incoming_bundle_order() {
+    let bundle = |sender: u32, height: u64, transaction_index: u32| IncomingBundle {
+        origin: Origin::chain(ChainId::root(sender)),
+        bundle: MessageBundle {
+            height: BlockHeight(height),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index,
+            messages: Vec::new(),
+        },
+        action: MessageAction::Accept,
+    };
+    let block_with_bundles = |bundles: Vec<IncomingBundle>| {
+        let proposed_block = bundles
+            .into_iter()
+            .fold(make_first_block(ChainId::root(1)), |block, bundle| {
+                block.with_incoming_bundle(bundle)
+            });
+        BlockExecutionOutcome::default().with(proposed_block)
+    };
+
+    // In order, interleaving two origins.
+    let block = block_with_bundles(vec![
+        bundle(2, 0, 0),
+        bundle(3, 5, 0),
+        bundle(2, 0, 1),
+        bundle(2, 1, 0),
+    ]);
+    assert!(block.validate_incoming_bundle_order().is_ok());
+
+    // Skipped bundles are allowed, as long as the order is kept.
+    let block = block_with_bundles(vec![bundle(2, 0, 1), bundle(2, 3, 0), bundle(3, 1, 2)]);
+    assert!(block.validate_incoming_bundle_order().is_ok());
+
+    // Out of order, or repeated.
+    let block = block_with_bundles(vec![bundle(2, 1, 0), bundle(3, 0, 0), bundle(2, 0, 4)]);
+    assert_matches!(
+        block.validate_incoming_bundle_order(),
+        Err(ChainError::IncomingBundleOutOfOrder {
+            origin,
+            previous_height: BlockHeight(1),
+            previous_index: 0,
+            height: BlockHeight(0),
+            index: 4,
+            ..
+        }) if origin.sender == ChainId::root(2)
+    );
+    let block = block_with_bundles(vec![bundle(2, 1, 0), bundle(2, 1, 0)]);
+    assert_matches!(
+        block.validate_incoming_bundle_order(),
+        Err(ChainError::IncomingBundleOutOfOrder {
+            previous_height: BlockHeight(1),
+            previous_index: 0,
+            height: BlockHeight(1),
+            index: 0,
+            ..
+        })
+    );
+}
+
+#[test]
+fn test_message_bundles_for_kind() {
+    let message = |recipient: u32, kind| {
+        let message = SystemMessage::Credit {
+            target: AccountOwner::CHAIN,
+            amount: Amount::ONE,
+            source: AccountOwner::CHAIN,
+        };
+        OutgoingMessage::new(ChainId::root(recipient), message).with_kind(kind)
+    };
+    let mut block = make_block(&[1, 2]);
+    block.body.messages = vec![
+        vec![
+            message(2, MessageKind::Simple),
+            message(2, MessageKind::Tracked),
+            message(3, MessageKind::Tracked),
+        ],
+        vec![
+            message(3, MessageKind::Simple),
+            message(2, MessageKind::Tracked),
+        ],
+    ];
+    let (proposed_block, outcome) = block.into_proposal();
+    let block = Block::new(proposed_block, outcome);
+    let certificate_hash = CryptoHash::test_hash("certificate");
+    let bundle_indices = |kind| {
+        block
+            .message_bundles_for_kind(&Medium::Direct, ChainId::root(2), certificate_hash, kind)
+            .map(|(_, bundle)| {
+                let indices = bundle.messages.iter().map(|message| message.index);
+                (bundle.transaction_index, indices.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        bundle_indices(MessageKind::Tracked),
+        [(0, vec![1]), (1, vec![4])]
+    );
+    assert_eq!(bundle_indices(MessageKind::Simple), [(0, vec![0])]);
+    assert_eq!(bundle_indices(MessageKind::Protected), []);
+    let all_bundles = block
+        .message_bundles_for(&Medium::Direct, ChainId::root(2), certificate_hash)
+        .count();
+    assert_eq!(all_bundles, 2);
+}
+
+#[test]
+fn test_message_bundles_for_subscriber() {
+    let application_id = ApplicationId::new(CryptoHash::test_hash("application"));
+    let channel = |name: u8| ChannelName::from(vec![name]);
+    let broadcast = |name: u8| OutgoingMessage {
+        destination: Destination::Subscribers(channel(name)),
+        ..OutgoingMessage::new(
+            ChainId::root(0),
+            Message::User {
+                application_id,
+                bytes: vec![name],
+            },
+        )
+    };
+    let (proposed_block, mut outcome) = make_block(&[1, 2]).into_proposal();
+    outcome.messages[0] = vec![broadcast(1)];
+    outcome.messages[1].push(broadcast(2));
+    let block = Block::new(proposed_block, outcome);
+    let certificate_hash = CryptoHash::test_hash("certificate");
+    let recipient = ChainId::root(3);
+    let is_subscribed = |name: &ChannelName, chain_id| *name == channel(1) && chain_id == recipient;
+
+    let bundles = |medium: Medium| {
+        block
+            .message_bundles_for_subscriber(&medium, recipient, certificate_hash, is_subscribed)
+            .map(|(_, bundle)| bundle)
+            .collect::<Vec<_>>()
+    };
+    let medium = |name| {
+        Medium::Channel(ChannelFullName {
+            application_id,
+            name: channel(name),
+        })
+    };
+    let subscribed = bundles(medium(1));
+    assert_eq!(subscribed.len(), 1);
+    assert_eq!(subscribed[0].transaction_index, 0);
+    assert_eq!(
+        subscribed,
+        block
+            .message_bundles_for(&medium(1), recipient, certificate_hash)
+            .map(|(_, bundle)| bundle)
+            .collect::<Vec<_>>()
+    );
+    assert!(bundles(medium(2)).is_empty());
+    assert_eq!(
+        block
+            .message_bundles_for(&medium(2), recipient, certificate_hash)
+            .count(),
+        1
+    );
+
+    // Direct messages are not subject to the subscription check.
+    let direct = block
+        .message_bundles_for_subscriber(
+            &Medium::Direct,
+            ChainId::root(2),
+            certificate_hash,
+            |_, _| false,
+        )
+        .count();
+    assert_eq!(direct, 1);
+}
+
+#[test]
+fn test_coalesced_bundles_for() {
+    let message = OutgoingMessage::new(
+        ChainId::root(2),
+        SystemMessage::Credit {
+            target: AccountOwner::CHAIN,
+            amount: Amount::ONE,
+            source: AccountOwner::CHAIN,
+        },
+    );
+    let (proposed_block, mut outcome) = make_block(&[1, 2, 3]).into_proposal();
+    outcome.messages = vec![
+        vec![message.clone()],
+        Vec::new(),
+        vec![message.clone(), message],
+    ];
+    let block = Block::new(proposed_block, outcome);
+    let certificate_hash = CryptoHash::test_hash("certificate");
+
+    let bundles = block.coalesced_bundles_for(&Medium::Direct, ChainId::root(2), certificate_hash);
+    assert_eq!(bundles.len(), 1);
+    let bundle = &bundles[0];
+    assert_eq!(bundle.height, block.header.height);
+    assert_eq!(bundle.transaction_index, 0);
+    let indices = bundle
+        .messages
+        .iter()
+        .map(|message| message.index)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, [0, 1, 2]);
+    assert_eq!(bundle.messages[1].message, bundle.messages[2].message);
+    let per_transaction = block
+        .message_bundles_for(&Medium::Direct, ChainId::root(2), certificate_hash)
+        .flat_map(|(_, bundle)| bundle.messages)
+        .collect::<Vec<_>>();
+    assert_eq!(bundle.messages, per_transaction);
+
+    assert!(block
+        .coalesced_bundles_for(&Medium::Direct, ChainId::root(3), certificate_hash)
+        .is_empty());
+}
+
+#[test]
+fn test_message_bundles_for_rev() {
+    let block = make_block(&[1, 2, 3, 4]);
+    let certificate_hash = CryptoHash::test_hash("certificate");
+    let indexed_messages = |bundles: Vec<(Epoch, MessageBundle)>| {
+        bundles
+            .into_iter()
+            .flat_map(|(_, bundle)| {
+                let transaction_index = bundle.transaction_index;
+                bundle
+                    .messages
+                    .into_iter()
+                    .map(move |message| (transaction_index, message.index, message))
+            })
+            .collect::<Vec<_>>()
+    };
+    let forward = indexed_messages(
+        block
+            .message_bundles_for(&Medium::Direct, ChainId::root(2), certificate_hash)
+            .collect(),
+    );
+    let mut reverse = indexed_messages(
+        block
+            .message_bundles_for_rev(&Medium::Direct, ChainId::root(2), certificate_hash)
+            .collect(),
+    );
+    assert_eq!(
+        forward
+            .iter()
+            .map(|(transaction_index, _, _)| *transaction_index)
+            .collect::<Vec<_>>(),
+        [1, 2, 2, 3, 3, 3]
+    );
+    assert_eq!(
+        reverse
+            .iter()
+            .map(|(transaction_index, _, _)| *transaction_index)
+            .collect::<Vec<_>>(),
+        [3, 3, 3, 2, 2, 1]
+    );
+    // Bundles are reversed, while messages within a bundle keep their order.
+    reverse.sort_by_key(|(_, index, _)| *index);
+    assert_eq!(forward, reverse);
+    for (_, index, message) in &forward {
+        let message_id = block.message_id(*index);
+        let outgoing_message = block.message_by_id(&message_id).unwrap();
+        assert_eq!(*message, outgoing_message.clone().into_posted(*index));
+    }
+}
+
+#[test]
+fn test_confirmed_block_matches_validated() {
+    let block = make_block(&[1, 2]);
+    let validated = ValidatedBlock::new(block.clone());
+    let confirmed = ConfirmedBlock::new(block.clone());
+    assert!(confirmed.matches_validated(&validated));
+    assert!(ConfirmedBlock::from_hashed(validated.inner().clone()).matches_validated(&validated));
+
+    let other_validated = ValidatedBlock::new(make_block(&[1, 3]));
+    assert!(!confirmed.matches_validated(&other_validated));
+    let mut later_block = block;
+    later_block.header.height = BlockHeight(1);
+    assert!(!ConfirmedBlock::new(later_block).matches_validated(&validated));
+}
+
+#[test]
+fn test_confirmed_block_from_validated() {
+    let block = make_block(&[1, 2]);
+    let validated = ValidatedBlock::new(block.clone());
+    let hash = validated.inner().hash();
+    let confirmed = ConfirmedBlock::from(validated);
+    assert_eq!(confirmed.inner().hash(), hash);
+    assert_eq!(confirmed, ConfirmedBlock::new(block));
+}
+
+#[test]
+fn test_try_message_by_id() {
+    let block = make_block(&[1, 2, 3]);
+    let message_id = block.message_id(2);
+    assert_eq!(
+        block.try_message_by_id(&message_id),
+        Ok(&block.messages()[2][1])
+    );
+
+    let wrong_chain = MessageId {
+        chain_id: ChainId::root(2),
+        ..message_id
+    };
+    assert_eq!(
+        block.try_message_by_id(&wrong_chain),
+        Err(MessageLookupError::WrongChain {
+            requested: ChainId::root(2),
+            block: ChainId::root(1),
+        })
+    );
+    let wrong_height = MessageId {
+        height: BlockHeight(5),
+        ..message_id
+    };
+    assert_eq!(
+        block.try_message_by_id(&wrong_height),
+        Err(MessageLookupError::WrongHeight {
+            requested: BlockHeight(5),
+            block: BlockHeight(0),
+        })
+    );
+    let out_of_range = block.message_id(3);
+    assert_eq!(
+        block.try_message_by_id(&out_of_range),
+        Err(MessageLookupError::IndexOutOfRange {
+            requested: 3,
+            available: 3,
+        })
+    );
+    assert_eq!(block.message_by_id(&out_of_range), None);
+}
+
+#[test]
+fn test_block_builder() {
+    let chain_id = ChainId::root(1);
+    let incoming_bundle = IncomingBundle {
+        origin: Origin::chain(ChainId::root(2)),
+        bundle: MessageBundle {
+            height: BlockHeight(0),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index: 0,
+            messages: Vec::new(),
+        },
+        action: MessageAction::Accept,
+    };
+    let operation = SystemOperation::PublishDataBlob {
+        blob_hash: CryptoHash::test_hash("blob"),
+    };
+    let message = OutgoingMessage::new(
+        ChainId::root(2),
+        SystemMessage::Credit {
+            target: AccountOwner::CHAIN,
+            amount: Amount::ONE,
+            source: AccountOwner::CHAIN,
+        },
+    );
+    let block = BlockBuilder::new(chain_id, CryptoHash::test_hash("state"))
+        .with_height(BlockHeight(3), CryptoHash::test_hash("previous"))
+        .with_timestamp(5)
+        .with_incoming_bundle(incoming_bundle.clone())
+        .with_transaction_oracle_responses(vec![OracleResponse::Assert])
+        .with_operation(operation.clone())
+        .with_transaction_messages(vec![message.clone()])
+        .with_transaction_events(vec![Event {
+            stream_id: StreamId::system("stream"),
+            index: 0,
+            value: vec![1],
+        }])
+        .with_operation_result(OperationResult(vec![2]))
+        .with_previous_message_block(ChainId::root(2), CryptoHash::test_hash("sender"))
+        .build()
+        .unwrap();
+    assert!(block.validate_hashes().is_ok());
+    assert_eq!(block.header.height, BlockHeight(3));
+    assert_eq!(block.body.incoming_bundles, vec![incoming_bundle.clone()]);
+    assert_eq!(block.body.operations, [Operation::from(operation.clone())]);
+    assert_eq!(block.body.messages, [Vec::new(), vec![message.clone()]]);
+    assert_eq!(block.body.oracle_responses[0], [OracleResponse::Assert]);
+    assert_eq!(block.body.operation_results, [OperationResult(vec![2])]);
+    assert_eq!(block.body.blobs.len(), 2);
+
+    let builder = BlockBuilder::new(chain_id, CryptoHash::test_hash("state"));
+    assert_eq!(
+        builder
+            .clone()
+            .with_operation(operation)
+            .with_incoming_bundle(incoming_bundle.clone())
+            .build(),
+        Err(BlockBuilderError::IncomingBundleAfterOperation)
+    );
+    assert_eq!(
+        builder
+            .clone()
+            .with_transaction_messages(vec![message])
+            .build(),
+        Err(BlockBuilderError::MissingTransaction)
+    );
+    assert_eq!(
+        builder
+            .with_incoming_bundle(incoming_bundle)
+            .with_operation_result(OperationResult::default())
+            .build(),
+        Err(BlockBuilderError::MissingOperation)
+    );
+}
+
+#[test]
+fn test_signed_block() {
+    use linera_base::crypto::AccountSecretKey;
+
+    let secret = AccountSecretKey::generate();
+    let other_secret = AccountSecretKey::generate();
+    let block = Hashed::new(make_block(&[1, 2]));
+    let signed_block = SignedBlock::new(block.clone(), &secret);
+    assert_eq!(signed_block.author, AccountOwner::from(secret.public()));
+    assert!(signed_block.verify().is_ok());
+
+    // The author must match the public key.
+    let mut swapped = signed_block.clone();
+    swapped.author = other_secret.public().into();
+    assert!(swapped.verify().is_err());
+    let mut swapped = signed_block.clone();
+    swapped.public_key = other_secret.public();
+    swapped.author = other_secret.public().into();
+    assert!(swapped.verify().is_err());
+
+    // The signature is only valid for the signed block.
+    let mut other = signed_block;
+    other.block = Hashed::new(make_block(&[1, 3]));
+    assert!(other.verify().is_err());
+    assert!(SignedBlock::new(other.block, &other_secret)
+        .verify()
+        .is_ok());
+}
+
+#[test]
+fn test_signed_timeout() {
+    use linera_base::crypto::AccountSecretKey;
+
+    let secret = AccountSecretKey::generate();
+    let other_secret = AccountSecretKey::generate();
+    let timeout = Timeout::new(ChainId::root(1), BlockHeight(3), Epoch::ZERO);
+    let signed_timeout = SignedTimeout::new(timeout, &secret);
+    assert_eq!(signed_timeout.author, AccountOwner::from(secret.public()));
+    assert!(signed_timeout.verify().is_ok());
+
+    // A signature from another owner is rejected.
+    let mut wrong_owner = signed_timeout.clone();
+    wrong_owner.author = other_secret.public().into();
+    wrong_owner.public_key = other_secret.public();
+    assert!(wrong_owner.verify().is_err());
+    let mut wrong_owner = signed_timeout.clone();
+    wrong_owner.author = other_secret.public().into();
+    assert!(wrong_owner.verify().is_err());
+
+    // The signature does not cover a timeout in another epoch.
+    let mut other_epoch = signed_timeout;
+    other_epoch.timeout = Timeout::new(ChainId::root(1), BlockHeight(3), Epoch(1));
+    assert!(other_epoch.verify().is_err());
+}
+
+#[test]
+fn test_conversion_error() {
+    use crate::types::{
+        Certificate, CertificateKind, ConfirmedBlockCertificate, TimeoutCertificate,
+        ValidatedBlockCertificate,
+    };
+
+    let block = make_block(&[1]);
+    let confirmed: Certificate =
+        ConfirmedBlockCertificate::new(ConfirmedBlock::new(block.clone()), Round::Fast, vec![])
+            .into();
+    let validated: Certificate =
+        ValidatedBlockCertificate::new(ValidatedBlock::new(block), Round::Fast, vec![]).into();
+    let timeout: Certificate = TimeoutCertificate::new(
+        Timeout::new(ChainId::root(1), BlockHeight(0), Epoch::ZERO),
+        Round::Fast,
+        vec![],
+    )
+    .into();
+
+    let error = ConfirmedBlockCertificate::try_from(timeout.clone()).unwrap_err();
+    assert_eq!(error.expected(), "confirmed");
+    assert_eq!(error.found(), CertificateKind::Timeout);
+    assert_eq!(
+        error.to_string(),
+        "Expected a `ConfirmedBlockCertificate` value, found a Timeout certificate"
+    );
+    let error = ValidatedBlockCertificate::try_from(confirmed.clone()).unwrap_err();
+    assert_eq!(error.expected(), "validated");
+    assert_eq!(error.found(), CertificateKind::Confirmed);
+    let error = TimeoutCertificate::try_from(validated.clone()).unwrap_err();
+    assert_eq!(error.expected(), "timeout");
+    assert_eq!(error.found(), CertificateKind::Validated);
+
+    assert!(ConfirmedBlockCertificate::try_from(confirmed).is_ok());
+    assert!(ValidatedBlockCertificate::try_from(validated).is_ok());
+    assert!(TimeoutCertificate::try_from(timeout).is_ok());
+}
+
+#[test]
+fn test_timeout_ordering() {
+    let chain1 = ChainId::root(1);
+    let chain2 = ChainId::root(2);
+    let timeout = Timeout::new(chain1, BlockHeight(5), Epoch::from(1));
+    let later_height = Timeout::new(chain1, BlockHeight(6), Epoch::from(1));
+    let later_epoch = Timeout::new(chain1, BlockHeight(0), Epoch::from(2));
+    let other_chain = Timeout::new(chain2, BlockHeight(9), Epoch::from(3));
+
+    assert!(timeout < later_height);
+    assert!(later_height < later_epoch);
+    assert_eq!(
+        timeout.cmp(&Timeout::new(chain1, BlockHeight(5), Epoch::from(1))),
+        Ordering::Equal
+    );
+    // Chain IDs are hashes, so only timeouts of the same chain have a meaningful order.
+    let mut timeouts = vec![
+        later_epoch.clone(),
+        timeout.clone(),
+        other_chain.clone(),
+        later_height.clone(),
+    ];
+    timeouts.sort();
+    timeouts.retain(|timeout| timeout.chain_id() == chain1);
+    assert_eq!(
+        timeouts,
+        [timeout.clone(), later_height.clone(), later_epoch.clone()]
+    );
+
+    assert!(later_height.supersedes(&timeout));
+    assert!(later_epoch.supersedes(&later_height));
+    assert!(!timeout.supersedes(&later_height));
+    assert!(!timeout.supersedes(&timeout));
+    assert!(!other_chain.supersedes(&timeout));
+}
+
+#[test]
+fn test_published_application_ids() {
+    let module_id = ModuleId::new(
+        CryptoHash::test_hash("contract"),
+        CryptoHash::test_hash("service"),
+        VmRuntime::Wasm,
+    );
+    let description = |application_index| ApplicationDescription {
+        module_id,
+        creator_chain_id: ChainId::root(1),
+        block_height: BlockHeight(0),
+        application_index,
+        parameters: Vec::new(),
+        required_application_ids: Vec::new(),
+    };
+    let create_application = SystemOperation::CreateApplication {
+        module_id,
+        parameters: Vec::new(),
+        instantiation_argument: Vec::new(),
+        required_application_ids: Vec::new(),
+    };
+    let data_blob = Blob::new_data(b"data".to_vec());
+    let block = BlockBuilder::new(ChainId::root(1), CryptoHash::test_hash("state"))
+        .with_operation(create_application.clone())
+        .with_transaction_blobs(vec![Blob::new_application_description(&description(0))])
+        .with_operation(SystemOperation::PublishDataBlob {
+            blob_hash: data_blob.id().hash,
+        })
+        .with_operation(create_application)
+        .with_transaction_blobs(vec![Blob::new_application_description(&description(1))])
+        .build()
+        .unwrap();
+
+    let expected = BTreeSet::from([
+        ApplicationId::from(&description(0)),
+        ApplicationId::from(&description(1)),
+    ]);
+    assert_eq!(block.published_application_ids().unwrap(), expected);
+    assert_eq!(block.published_blob_ids().len(), 1);
+}
@@ -2,6 +2,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use assert_matches::assert_matches;
 use linera_base::{
     crypto::{Ed25519SecretKey, Secp256k1SecretKey, ValidatorKeypair},
     data_types::Amount,
@@ -10,7 +11,7 @@ use linera_base::{
 use super::*;
 use crate::{
     block::{ConfirmedBlock, ValidatedBlock},
-    test::{make_first_block, BlockTestExt},
+    test::{make_first_block, BlockTestExt, MessageTestExt},
 };
 
 #[test]
@@ -136,3 +137,102 @@ fn test_certificates() {
         .is_none());
     assert!(builder.append(v3.public_key, v3.signature).is_err());
 }
+
+#[test]
+fn test_estimate_size_is_upper_bound() {
+    let application_id = ApplicationId::new(CryptoHash::test_hash("application"));
+    let user_operation = |len: usize| Operation::User {
+        application_id,
+        bytes: vec![1; len],
+    };
+    let bundle = |lengths: &[usize]| IncomingBundle {
+        origin: Origin::chain(ChainId::root(2)),
+        bundle: MessageBundle {
+            height: BlockHeight(3),
+            timestamp: Timestamp::from(4),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index: 1,
+            messages: lengths
+                .iter()
+                .zip(0..)
+                .map(|(len, index)| {
+                    Message::User {
+                        application_id,
+                        bytes: vec![2; *len],
+                    }
+                    .to_posted(index, MessageKind::Tracked)
+                })
+                .collect(),
+        },
+        action: MessageAction::Accept,
+    };
+
+    let block = make_first_block(ChainId::root(1));
+    let blocks = [
+        block.clone(),
+        block
+            .clone()
+            .with_simple_transfer(ChainId::root(2), Amount::ONE),
+        block
+            .clone()
+            .with_operation(user_operation(0))
+            .with_operation(user_operation(127))
+            .with_operation(user_operation(128))
+            .with_operation(user_operation(20_000)),
+        block
+            .clone()
+            .with_incoming_bundle(bundle(&[0, 300]))
+            .with_incoming_bundle(bundle(&[100_000])),
+        block
+            .with_authenticated_signer(Some(AccountOwner::from(CryptoHash::test_hash("owner"))))
+            .with_incoming_bundle(bundle(&[16_384]))
+            .with_simple_transfer(ChainId::root(3), Amount::ONE)
+            .with_operation(user_operation(1)),
+    ];
+    for block in blocks {
+        let exact = bcs::serialized_size(&block).unwrap();
+        let estimate = block.estimate_size().unwrap();
+        assert!(estimate >= exact, "{estimate} < {exact}");
+        assert!(block.check_proposal_size(exact as u64).is_ok());
+        assert_matches!(
+            block.check_proposal_size(exact as u64 - 1),
+            Err(ChainError::BlockProposalTooLarge { size, limit })
+                if size == exact as u64 && limit == exact as u64 - 1
+        );
+    }
+}
+
+#[test]
+fn test_with_rejected_bundles_only() {
+    let bundle = |height: u64, action| IncomingBundle {
+        origin: Origin::chain(ChainId::root(2)),
+        bundle: MessageBundle {
+            height: BlockHeight(height),
+            timestamp: Timestamp::from(0),
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            transaction_index: 0,
+            messages: vec![Message::System(SystemMessage::Credit {
+                target: AccountOwner::CHAIN,
+                amount: Amount::ONE,
+                source: AccountOwner::CHAIN,
+            })
+            .to_posted(0, MessageKind::Tracked)],
+        },
+        action,
+    };
+    let block = make_first_block(ChainId::root(1))
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_incoming_bundle(bundle(0, MessageAction::Accept));
+    assert!(!block.has_only_rejected_messages());
+
+    let block = block.with_rejected_bundles_only([
+        bundle(1, MessageAction::Accept),
+        bundle(2, MessageAction::Reject),
+    ]);
+    assert!(block.has_only_rejected_messages());
+    assert!(block.operations.is_empty());
+    assert_eq!(block.incoming_bundles.len(), 2);
+    assert_eq!(block.incoming_bundles[0].bundle.height, BlockHeight(1));
+    assert_eq!(block.incoming_bundles[1].bundle.height, BlockHeight(2));
+    assert_eq!(block.chain_id, ChainId::root(1));
+}
@@ -2340,7 +2340,7 @@ where
         result,
         Err(ChainClientError::LocalNodeError(
             LocalNodeError::WorkerError(WorkerError::ChainError(chain_error))
-        )) if matches!(*chain_error, ChainError::BlockProposalTooLarge)
+        )) if matches!(*chain_error, ChainError::BlockProposalTooLarge { .. })
     );
 
     assert_matches!(
@@ -463,7 +463,7 @@ where
             }
         };
         match vote {
-            Some(vote) if vote.public_key == self.remote_node.public_key => {
+            Some(vote) if vote.public_key.ct_eq(&self.remote_node.public_key) => {
                 vote.check()?;
                 Ok(vote)
             }